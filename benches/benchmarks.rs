@@ -1,13 +1,165 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
 use todo_swamp::*;
+use todo_swamp::generator::{Generator, GeneratorConfig};
+use todo_swamp::sharded::ShardedTodoList;
 
-// // pub fn criterion_benchmark(c: &mut Criterion) {
-// //     c.bench_function("fib 20", |b| b.iter(|| fibonacci(black_box(20))));
-// // }
+use std::time::Duration;
 
-// criterion_group!(benches, criterion_benchmark);
-// criterion_main!(benches);
+//list sizes each operation is measured at
+const SIZES: [usize; 2] = [1_000, 10_000];
+//list size the tries' id sets are compared at, big enough for most nodes to hold thousands of ids,
+//and the searches answered there per iteration, as each one visits most of the trie
+const DENSE_SIZE: usize = 20_000;
+const DENSE_SEARCHES: usize = 10;
+//searches answered per iteration, so one unlucky term doesn't decide a measurement
+const SEARCHES: usize = 100;
 
-fn main() { //I don't actually need criterion at the moment, so this just gets it to compile
-    
-}
\ No newline at end of file
+//runs a benchmark function once per backend, naming each after the application's --backend option
+macro_rules! for_each_backend {
+    ($bench:ident, $($arg:expr),*) => {
+        $bench::<TodoList>("naive", $($arg),*);
+        $bench::<TodoList2>("naive2", $($arg),*);
+        $bench::<TriedoList<Trie1>>("trie1", $($arg),*);
+        $bench::<TriedoList<Trie2>>("trie2", $($arg),*);
+        $bench::<TriedoList<Trie3>>("trie3", $($arg),*);
+        $bench::<TriedoList<Trie4>>("trie4", $($arg),*);
+        $bench::<TriedoList<Trie5>>("trie5", $($arg),*);
+        $bench::<ShardedTodoList>("sharded", $($arg),*);
+    };
+}
+
+type Item = (Vec<Word>, Vec<Tag>);
+
+//generated items to fill lists with, all from one vocabulary so that searches built from any of them find something
+fn items(count: usize) -> Vec<Item> {
+    let config = GeneratorConfig {
+        done_weight: 0,
+        search_weight: 0,
+        ..GeneratorConfig::new()
+    };
+    Generator::new(config).take(count).map(|line| match runner::parse_line(&line) {
+        Ok(Query::Add(description, tags)) => (description, tags),
+        other => panic!("generator only adds, got {:?}", other),
+    }).collect()
+}
+
+//generated searches, with terms as typed by people looking for something
+//(generators made with the same seed and word lengths draw from the same vocabulary as the items)
+fn searches(search_terms: (usize, usize), tag_term_chance: f64) -> Vec<SearchParams> {
+    let config = GeneratorConfig {
+        add_weight: 0,
+        done_weight: 0,
+        search_terms,
+        tag_term_chance,
+        ..GeneratorConfig::new()
+    };
+    Generator::new(config).take(SEARCHES).map(|line| match runner::parse_line(&line) {
+        Ok(Query::Search(sp)) => sp,
+        other => panic!("generator only searches, got {:?}", other),
+    }).collect()
+}
+
+//single word searches for a whole word from an item, or just its first letter, which matches far more
+fn word_searches(items: &[Item], whole: bool) -> Vec<SearchParams> {
+    items.iter().step_by(items.len() / SEARCHES).take(SEARCHES).map(|(description, _)| {
+        let word = description[0].value();
+        let term = if whole { word.to_owned() } else { word.chars().take(1).collect() };
+        SearchParams::new(vec![SearchWordOrTag::RawWord(term)])
+    }).collect()
+}
+
+//searches for the first letters of an item's words, each of which most items match somewhere,
+//so every term's ids have to be intersected with a large set
+fn letter_searches(items: &[Item]) -> Vec<SearchParams> {
+    items.iter().step_by(items.len() / DENSE_SEARCHES).take(DENSE_SEARCHES).map(|(description, _)| {
+        let terms = description.iter().take(3).map(|w| SearchWordOrTag::RawWord(w.value().chars().take(1).collect()));
+        SearchParams::new(terms.collect())
+    }).collect()
+}
+
+fn filled<T: TodoLister + Default>(items: &[Item]) -> T {
+    let mut tl = T::default();
+    for (description, tags) in items {
+        tl.push(description.clone(), tags.clone());
+    }
+    tl
+}
+
+fn bench_push<T: TodoLister + Default>(backend: &str, c: &mut Criterion, items: &[Item]) {
+    let mut group = c.benchmark_group("push");
+    group.bench_function(BenchmarkId::new(backend, items.len()), |b| b.iter_batched(
+        || items.to_vec(),
+        |items| {
+            let mut tl = T::default();
+            for (description, tags) in items {
+                black_box(tl.push(description, tags));
+            }
+            tl
+        },
+        BatchSize::LargeInput,
+    ));
+}
+
+//marks every tenth item done, which for the tries means pruning their ids out of the tree
+fn bench_done<T: TodoLister + Default + Clone>(backend: &str, c: &mut Criterion, items: &[Item]) {
+    let tl: T = filled(items);
+    let mut group = c.benchmark_group("done_with_index");
+    group.bench_function(BenchmarkId::new(backend, items.len()), |b| b.iter_batched(
+        || tl.clone(),
+        |mut tl| {
+            for i in (0..items.len()).step_by(10) {
+                black_box(tl.done_with_index(Index::new(i as u64)));
+            }
+            tl
+        },
+        BatchSize::LargeInput,
+    ));
+}
+
+fn bench_search<T: TodoLister + Default>(backend: &str, c: &mut Criterion, items: &[Item], kinds: &[(&str, Vec<SearchParams>)]) {
+    let tl: T = filled(items);
+    for (kind, searches) in kinds {
+        let mut group = c.benchmark_group(format!("search {}", kind));
+        group.bench_function(BenchmarkId::new(backend, items.len()), |b| b.iter(|| {
+            for sp in searches {
+                black_box(tl.search(sp.clone()));
+            }
+        }));
+    }
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    for &size in &SIZES {
+        let items = items(size);
+        let kinds = vec![
+            ("single word", searches((1, 1), 0.0)),
+            ("multi-word", searches((2, 3), 0.0)),
+            ("tag", searches((1, 1), 1.0)),
+            ("mixed", searches((2, 3), 0.5)),
+            ("short word", word_searches(&items, false)),
+            ("long word", word_searches(&items, true)),
+        ];
+        for_each_backend!(bench_push, c, &items);
+        for_each_backend!(bench_done, c, &items);
+        for_each_backend!(bench_search, c, &items, &kinds);
+    }
+
+    //the tries only differ in how they hold ids, so set them against each other where there are lots of ids to hold
+    let items = items(DENSE_SIZE);
+    let kinds = vec![("dense multi-letter", letter_searches(&items))];
+    bench_search::<TriedoList<Trie3>>("trie3", c, &items, &kinds);
+    bench_search::<TriedoList<Trie4>>("trie4", c, &items, &kinds);
+    bench_search::<TriedoList<Trie5>>("trie5", c, &items, &kinds);
+}
+
+//the slowest backends take a while to fill at the larger sizes, so keep the whole suite to a few minutes
+fn config() -> Criterion {
+    Criterion::default().sample_size(20).warm_up_time(Duration::from_secs(1)).measurement_time(Duration::from_secs(2))
+}
+
+criterion_group!{
+    name = benches;
+    config = config();
+    targets = criterion_benchmark
+}
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+#![no_main]
+//runs arbitrary sequences of adds, dones, tag edits and searches through every backend,
+//failing on any panic or any query which the backends don't all answer like TodoList does
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use todo_swamp::*;
+use todo_swamp::differential::Differential;
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+    Add(Vec<String>, Vec<String>),
+    Done(u16), //small, so that most dones name an item which exists
+    Search(Vec<(bool, String)>, Option<u8>, Option<u8>, Option<u8>, Option<u8>), //terms, then limit, offset and sort options and a view
+    Count(Vec<(bool, String)>),
+    Stats,
+    Tags(Option<String>),
+    DoneWhere(Vec<(bool, String)>),
+    TagWhere(bool, Vec<String>, Vec<(bool, String)>), //adding or removing, then the tags and the search
+    EditTags(u16, Vec<String>, Vec<String>), //tags to add, then tags to remove
+    Save(u8, Vec<(bool, String)>),
+    View(u8),
+}
+
+//a few names, so that views are often saved over and searches often name one which exists
+fn view_name(n: u8) -> String {
+    format!("v{}", n % 4)
+}
+
+//strings which aren't words are left out, and ops left without any words are skipped
+fn query(op: Op) -> Option<Query> {
+    let words = |strings: Vec<String>| strings.into_iter().filter(|s| parser::is_word(s)).collect::<Vec<_>>();
+    match op {
+        Op::Add(description, tags) => {
+            let description: Vec<Word> = words(description).iter().map(|w| Word::new(w)).collect();
+            let tags = words(tags).iter().map(|t| Tag::new(t)).collect();
+            if description.is_empty() { None } else { Some(Query::Add(description, tags)) }
+        },
+        Op::Done(i) => Some(Query::Done(Index::new(i.into()))),
+        Op::Search(terms, limit, offset, sort, view) => {
+            let sort = sort.map(|s| [SortOrder::Index, SortOrder::IndexDescending, SortOrder::Score][usize::from(s) % 3]);
+            let options = SearchOptions{limit: limit.map(usize::from), offset: offset.map(usize::from), sort};
+            search_params(terms).map(|params| Query::Search(SearchParams{params, options, views: view.map(view_name).into_iter().collect()}))
+        },
+        Op::Count(terms) => search_params(terms).map(|params| Query::Count(SearchParams::new(params))),
+        Op::Stats => Some(Query::Stats),
+        Op::Tags(prefix) => Some(Query::Tags(prefix.filter(|p| parser::is_word(p)))),
+        Op::DoneWhere(terms) => search_params(terms).map(|params| Query::DoneWhere(SearchParams::new(params))),
+        Op::TagWhere(adding, tags, terms) => {
+            let change = if adding { TagChange::Add } else { TagChange::Remove };
+            let tags: Vec<Tag> = words(tags).iter().map(|t| Tag::new(t)).collect();
+            if tags.is_empty() { None } else { search_params(terms).map(|params| Query::TagWhere(change, tags, SearchParams::new(params))) }
+        },
+        Op::Save(n, terms) => search_params(terms).map(|params| Query::Save(view_name(n), SearchParams::new(params))),
+        Op::View(n) => Some(Query::View(view_name(n))),
+        Op::EditTags(i, add, remove) => {
+            let edit = TagEdit{add: words(add).iter().map(|t| Tag::new(t)).collect(), remove: words(remove).iter().map(|t| Tag::new(t)).collect()};
+            if edit.add.is_empty() && edit.remove.is_empty() { None } else { Some(Query::EditTags(Index::new(i.into()), edit)) }
+        },
+    }
+}
+
+fn search_params(terms: Vec<(bool, String)>) -> Option<Vec<SearchWordOrTag>> {
+    let params: Vec<SearchWordOrTag> = terms.into_iter().filter(|(_, s)| parser::is_word(s)).map(|(is_tag, s)| {
+        if is_tag { SearchWordOrTag::RawTag(s) } else { SearchWordOrTag::RawWord(s) }
+    }).collect();
+    if params.is_empty() { None } else { Some(params) }
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut differential = Differential::new();
+    for q in ops.into_iter().filter_map(query) {
+        if let Err(mismatch) = differential.run_query(q) {
+            panic!("{}", mismatch);
+        }
+    }
+});
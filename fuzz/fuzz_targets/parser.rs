@@ -0,0 +1,12 @@
+#![no_main]
+//any text has to either fail to parse or give a query which prints back to text parsing to the same query
+use libfuzzer_sys::fuzz_target;
+use todo_swamp::*;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(line) = std::str::from_utf8(data) {
+        if let Ok((_, q)) = parser::query(line) {
+            assert_eq!(runner::parse_line(&q.to_string()), Ok(q));
+        }
+    }
+});
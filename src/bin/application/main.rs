@@ -0,0 +1,347 @@
+extern crate todo_swamp;
+
+mod repl;
+
+use todo_swamp::*;
+use todo_swamp::cache::CachedTodoList;
+use todo_swamp::profile::Profiler;
+use todo_swamp::sharded::ShardedTodoList;
+use todo_swamp::views::Views;
+
+use std::io::{self, prelude::*, IsTerminal};
+use std::fs;
+use std::net::{Ipv4Addr, TcpListener};
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::time;
+
+use clap::{CommandFactory, Parser, ValueEnum};
+use clap::error::ErrorKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Backend {
+    Naive,
+    Naive2,
+    Trie1,
+    Trie2,
+    Trie3,
+    Trie4,
+    Trie5,
+    Sharded,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "application", about = "Answers add, done and search queries against a todo list")]
+struct Options {
+    ///todo list implementation to answer queries with
+    #[arg(short, long, value_enum, default_value_t = Backend::Naive)]
+    backend: Backend,
+
+    ///number of shards the sharded backend searches in parallel, only with --backend sharded [default: one per cpu]
+    #[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u16).range(1..))]
+    shards: Option<u16>,
+
+    ///remember search results until a push or done could change them, reporting hits and misses with --stats
+    #[arg(long, conflicts_with = "correctness")]
+    cache: bool,
+
+    ///file to read queries from [default: standard input]
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    ///file to write results to [default: standard output]
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    ///treat every input line as a query, rather than the first as a query count
+    #[arg(long)]
+    no_count: bool,
+
+    ///start an interactive session [default: when standard input is a terminal and no input file is given]
+    #[arg(long, conflicts_with_all = ["input", "output", "timed", "bench", "correctness"])]
+    interactive: bool,
+
+    ///stop answering queries after this many milliseconds and report how many were answered
+    #[arg(short, long, value_name = "MILLIS")]
+    timed: Option<u128>,
+
+    ///answer at most this many queries
+    #[arg(short = 'n', long, value_name = "COUNT")]
+    commands: Option<usize>,
+
+    ///discard results rather than writing them, and report how long the queries took
+    #[arg(long, conflicts_with = "output")]
+    bench: bool,
+
+    ///run <DIR>/<TEST>.in through every backend, writing sorted results to <DIR>/correct/<TEST>_<backend>.out
+    #[arg(long, value_name = "TEST", conflicts_with_all = ["input", "output", "timed", "bench"])]
+    correctness: Option<String>,
+
+    ///directory holding correctness tests
+    #[arg(long, value_name = "DIR", default_value = "tests")]
+    tests_dir: String,
+
+    ///after answering queries, print a breakdown of the memory held by the list to standard error
+    #[arg(long, conflicts_with_all = ["interactive", "correctness", "tcp"])]
+    memory: bool,
+
+    ///after answering queries, print counts, timings and work done for each kind of query to standard error
+    #[arg(long, conflicts_with_all = ["interactive", "correctness", "tcp"])]
+    stats: bool,
+
+    ///append queries taking at least --slow-ms to this file, with their timings and work done
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["interactive", "correctness", "tcp"])]
+    slow_log: Option<PathBuf>,
+
+    ///how long a query has to take to be logged as slow
+    #[arg(long, value_name = "MILLIS", default_value_t = 10, requires = "slow_log")]
+    slow_ms: u64,
+
+    ///serve one shared list to clients connecting to this localhost tcp port
+    #[arg(long, value_name = "PORT", conflicts_with_all = ["input", "output", "interactive", "timed", "bench", "correctness"])]
+    tcp: Option<u16>,
+
+    ///serve one shared list to clients connecting to a unix domain socket created at this path
+    #[cfg(unix)]
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["input", "output", "interactive", "timed", "bench", "correctness", "tcp", "memory", "stats", "slow_log"])]
+    unix: Option<PathBuf>,
+}
+
+pub fn main() -> io::Result<()> {
+    let opts = Options::parse();
+    if opts.shards.is_some() && opts.backend != Backend::Sharded {
+        Options::command().error(ErrorKind::ArgumentConflict, "--shards can only be used with --backend sharded").exit();
+    }
+    if let Some(test) = &opts.correctness {
+        return correctness_all(&opts.tests_dir, test, opts.commands.unwrap_or(usize::MAX))
+    }
+    match opts.backend {
+        Backend::Naive => run_backend(TodoList::new(), &opts),
+        Backend::Naive2 => run_backend(TodoList2::new(), &opts),
+        Backend::Trie1 => run_backend(TriedoList::<Trie1>::new(), &opts),
+        Backend::Trie2 => run_backend(TriedoList::<Trie2>::new(), &opts),
+        Backend::Trie3 => run_backend(TriedoList::<Trie3>::new(), &opts),
+        Backend::Trie4 => run_backend(TriedoList::<Trie4>::new(), &opts),
+        Backend::Trie5 => run_backend(TriedoList::<Trie5>::new(), &opts),
+        Backend::Sharded => run_backend(sharded(opts.shards), &opts),
+    }
+}
+
+fn sharded(shards: Option<u16>) -> ShardedTodoList {
+    match shards {
+        Some(n) => ShardedTodoList::with_shards(n.into()),
+        None => ShardedTodoList::new(),
+    }
+}
+
+//wraps the list in a cache if one was asked for
+fn run_backend<T: TodoLister + Send + Sync + 'static>(tl: T, opts: &Options) -> io::Result<()> {
+    if !opts.cache {
+        return run(tl, opts)
+    }
+    let tl = CachedTodoList::new(tl);
+    let stats = tl.cache_stats();
+    run(tl, opts)?;
+    if opts.stats {
+        eprintln!("{}", stats);
+    }
+    Ok(())
+}
+
+//picks the run mode and input/output streams from the command line options
+fn run<T: TodoLister + Send + Sync + 'static>(mut tl: T, opts: &Options) -> io::Result<()> {
+    if let Some(port) = opts.tcp {
+        return server::serve_tcp(TcpListener::bind((Ipv4Addr::LOCALHOST, port))?, tl)
+    }
+    #[cfg(unix)]
+    if let Some(path) = &opts.unix {
+        return server::serve_unix(UnixListener::bind(path)?, tl)
+    }
+    if opts.interactive || (opts.input.is_none() && io::stdin().is_terminal()) { //typing by hand, so don't expect a query count
+        return repl::run(tl)
+    }
+    let input: Box<dyn BufRead> = match &opts.input {
+        Some(path) => Box::new(io::BufReader::new(fs::File::open(path)?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let mut queries = input.lines().map_while(Result::ok);
+    if !opts.no_count && queries.next().is_none() { //read first line as query count, loop on remaining lines
+        return Ok(())
+    }
+    let queries = queries.take(opts.commands.unwrap_or(usize::MAX));
+    let mut profiler = profiler(opts)?;
+
+    if opts.bench {
+        let start = time::Instant::now();
+        let count = match opts.timed {
+            Some(max_millis) => benchmark_run_timed(queries, &mut tl, &mut profiler, max_millis),
+            None => benchmark_run_count(queries, &mut tl, &mut profiler),
+        };
+        eprintln!("{} queries answered in {}ms", count, start.elapsed().as_millis());
+        report(&tl, &profiler, opts);
+        return Ok(())
+    }
+    let mut output: Box<dyn Write> = match &opts.output {
+        Some(path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+    match opts.timed {
+        Some(max_millis) => {
+            let count = timed_run(queries, &mut output, &mut tl, &mut profiler, max_millis)?;
+            eprintln!("{} queries answered in under {}ms", count, max_millis);
+        },
+        None => standard_run(queries, &mut output, &mut tl, &mut profiler)?,
+    }
+    output.flush()?;
+    report(&tl, &profiler, opts);
+    Ok(())
+}
+
+//a profiler if query stats or a slow query log were asked for
+fn profiler(opts: &Options) -> io::Result<Option<Profiler>> {
+    if !opts.stats && opts.slow_log.is_none() {
+        return Ok(None)
+    }
+    let profiler = Profiler::new(time::Duration::from_millis(opts.slow_ms));
+    Ok(Some(match &opts.slow_log {
+        Some(path) => {
+            let log = fs::OpenOptions::new().create(true).append(true).open(path)?;
+            profiler.with_slow_log(Box::new(io::LineWriter::new(log)))
+        },
+        None => profiler,
+    }))
+}
+
+//prints whatever was asked for about the finished run
+fn report<T: TodoLister>(tl: &T, profiler: &Option<Profiler>, opts: &Options) {
+    if opts.memory {
+        eprintln!("{}", tl.memory_usage());
+    }
+    if let (true, Some(profiler)) = (opts.stats, profiler) {
+        eprintln!("{}", profiler.stats());
+    }
+}
+
+//searches are saved for as long as the run lasts
+fn answer<T: TodoLister>(line: &str, tl: &mut T, views: &mut Views, profiler: &mut Option<Profiler>) -> Option<QueryResult> {
+    match profiler {
+        Some(profiler) => runner::run_line_profiled(line, tl, views, profiler),
+        None => runner::run_line(line, tl, views),
+    }
+}
+
+//answers every query, writing results to the output
+fn standard_run<T: TodoLister>(queries: impl Iterator<Item = String>, buffer_out: &mut dyn Write, tl: &mut T, profiler: &mut Option<Profiler>) -> io::Result<()> {
+    let mut views = Views::new();
+    for l in queries {
+        if let Some(r) = answer(&l, tl, &mut views, profiler) {
+            writeln!(buffer_out, "{}", r)?;
+        }
+    }
+    Ok(())
+}
+
+//writes results to the output
+//returns the number of queries it was able to respond to, in the specified number of milliseconds
+fn timed_run<T: TodoLister>(queries: impl Iterator<Item = String>, buffer_out: &mut dyn Write, tl: &mut T, profiler: &mut Option<Profiler>, max_millis: u128) -> io::Result<usize> {
+    let mut views = Views::new();
+    let mut count = 0;
+    let start = time::Instant::now();
+    for l in queries {
+        if start.elapsed().as_millis() > max_millis {
+            break
+        }
+        if let Some(r) = answer(&l, tl, &mut views, profiler) {
+            writeln!(buffer_out, "{}", r)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+//returns the number of commands it was able to respond to, in the specified number of milliseconds
+fn benchmark_run_timed<T: TodoLister>(queries: impl Iterator<Item = String>, tl: &mut T, profiler: &mut Option<Profiler>, max_millis: u128) -> usize {
+    let mut views = Views::new();
+    let mut count = 0;
+    let start = time::Instant::now();
+    for l in queries {
+        if start.elapsed().as_millis() > max_millis {
+            break
+        }
+        if let Some(result) = answer(&l, tl, &mut views, profiler) {
+            black_box(result);
+            count += 1;
+        }
+    }
+    count
+}
+
+//takes as much time as it needs, to process every query given to it (limit these with --commands)
+//returns the number of commands it responded to
+fn benchmark_run_count<T: TodoLister>(queries: impl Iterator<Item = String>, tl: &mut T, profiler: &mut Option<Profiler>) -> usize {
+    let mut views = Views::new();
+    let mut count = 0;
+    for l in queries {
+        if let Some(result) = answer(&l, tl, &mut views, profiler) {
+            black_box(result);
+            count += 1;
+        }
+    }
+    count
+}
+
+//runs correctness_run on all current implementations to stress test for implementation incorrectness
+fn correctness_all(dir: &str, test: &str, num_commands: usize) -> io::Result<()> {
+    correctness_run(dir, test, "naive", TodoList::new(), num_commands)?;
+    correctness_run(dir, test, "naive2", TodoList2::new(), num_commands)?;
+    correctness_run(dir, test, "trie1", TriedoList::<Trie1>::new(), num_commands)?;
+    correctness_run(dir, test, "trie2", TriedoList::<Trie2>::new(), num_commands)?;
+    correctness_run(dir, test, "trie3", TriedoList::<Trie3>::new(), num_commands)?;
+    correctness_run(dir, test, "trie4", TriedoList::<Trie4>::new(), num_commands)?;
+    correctness_run(dir, test, "trie5", TriedoList::<Trie5>::new(), num_commands)?;
+    correctness_run(dir, test, "sharded", ShardedTodoList::new(), num_commands)?;
+    Ok(())
+}
+
+//takes input from the specified file
+//takes as much time as it needs, to process the specified number of commands
+//outputs to an output file (with search query results sorted so that outputs from different implementations can be easily compared for equality)
+fn correctness_run<T: TodoLister>(dir: &str, name: &str, append: &str, mut tl: T, num_commands: usize) -> io::Result<()> {
+    let file_in = fs::File::open(format!("{}/{}.in", dir, name))?;
+    fs::create_dir_all(format!("{}/correct", dir))?;
+    let file_out = fs::File::create(format!("{}/correct/{}_{}.out", dir, name, append))?;
+    let mut lines_in = io::BufReader::new(file_in).lines();
+    let mut buffer_out = io::BufWriter::new(file_out);
+    let mut views = Views::new();
+    let mut count = 0;
+    if let Some(Ok(_s)) = lines_in.next() {
+        for line in lines_in {
+            if count >= num_commands {
+                break
+            }
+            if let Ok(l) = line {
+                if let Some(mut r) = runner::run_line(&l, &mut tl, &mut views) {
+                    if let QueryResult::Found(results) = &mut r {
+                        results.sort(); //sorted results makes resulting test files easy to check for equality
+                    }
+                    writeln!(buffer_out, "{}", r)?;
+                    count += 1;
+                }
+                else {
+                    panic!(); //make bugs more apparent
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+//copied from criterion: https://docs.rs/criterion/0.3.4/src/criterion/lib.rs.html#174-180
+pub fn black_box<T>(dummy: T) -> T {
+    unsafe {
+        let ret = std::ptr::read_volatile(&dummy);
+        std::mem::forget(dummy);
+        ret
+    }
+}
@@ -0,0 +1,191 @@
+//Interactive mode: a line-editing prompt in front of runner::try_run_line_in_session, with history, tab completion and tabulated search results
+
+use todo_swamp::*;
+use todo_swamp::session::SearchSession;
+use todo_swamp::views::Views;
+
+use std::collections::BTreeSet;
+use std::io;
+
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use unicode_width::UnicodeWidthStr;
+
+const PROMPT: &str = "todo> ";
+const COMMANDS: [&str; 12] = ["add", "done", "search", "count", "stats", "tags", "tag", "save", "view", "help", "quit", "exit"];
+const HELP: &str = "\
+add \"<word> [<word>...]\" [#<tag>...]  add an item, printing its index
+done <index>                          mark the item with the given index as done
+done where <search>                   mark every open item a search lists as done
+tag <index> [+#<tag>] [-#<tag>]...    add tags to and remove tags from the item with the given index
+search <word or #tag> [...]           list open items matching every search term as a subsequence
+                                      (a search extending the one before it only looks through that one's results)
+  ... limit:<n> offset:<n>            show only a page of the results, in index order unless sorted otherwise
+  ... sort:<index|-index|score>       sort results by index, by index descending, or best matches first
+  ... @<name>                         take in the terms (and any options not given) of a saved search
+count <word or #tag> [...]            count the open items a search would list
+stats                                 count open and done items, overall and for each tag
+tags [<prefix>]                       list every tag (or those starting with the prefix) with its open item count
+tag <add|remove> #<tag>... where <search>
+                                      add tags to (or remove them from) every open item a search lists
+save <name> <search>                  save a search under a name, replacing any saved under it before
+view <name>                           list what a saved search finds now
+help                                  show this message
+quit, exit                            leave (as does ctrl-d)
+
+<tab> completes commands, known #tags, saved @names and the indices of open items, arrow keys recall history";
+
+//everything the completer knows about, learned from the results of previous queries
+#[derive(Default)]
+struct ReplHelper {
+    tags: BTreeSet<String>,
+    open: BTreeSet<u64>,
+    views: BTreeSet<String>,
+}
+impl ReplHelper {
+    fn record(&mut self, line: &str, result: &QueryResult) {
+        match result {
+            QueryResult::Added(item) => {
+                self.open.insert(item.index.value());
+                self.tags.extend(item.tags.iter().map(|t| t.value().to_owned()));
+            },
+            QueryResult::Done => {
+                if let Ok((_, Query::Done(idx))) = parser::query(line) {
+                    self.open.remove(&idx.value());
+                }
+            },
+            QueryResult::Tags(tags) => self.tags.extend(tags.keys().cloned()),
+            //which items a bulk done closed isn't known, so their indices are still offered
+            QueryResult::Affected(_) => {
+                if let Ok((_, Query::TagWhere(TagChange::Add, tags, _))) = parser::query(line) {
+                    self.tags.extend(tags.iter().map(|t| t.value().to_owned()));
+                }
+            },
+            QueryResult::Edited(item) => self.tags.extend(item.tags.iter().map(|t| t.value().to_owned())),
+            QueryResult::Saved(name) => {
+                self.views.insert(name.clone());
+            },
+            QueryResult::Found(_) | QueryResult::Count(_) | QueryResult::Stats(_) => (),
+        }
+    }
+}
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos].rfind([' ', '\t']).map_or(0, |i| i + 1);
+        let partial = &line[start..pos];
+        let candidates: Vec<String> = if start == 0 {
+            COMMANDS.iter().filter(|c| c.starts_with(partial)).map(|c| format!("{} ", c)).collect()
+        }
+        else if let Some(partial_tag) = partial.strip_prefix('#') {
+            let partial_tag = normalise(partial_tag);
+            self.tags.iter().filter(|t| normalise(t).starts_with(&partial_tag)).map(|t| format!("#{}", t)).collect()
+        }
+        else if let Some(partial_view) = partial.strip_prefix('@') {
+            self.views.iter().filter(|v| v.starts_with(partial_view)).map(|v| format!("@{}", v)).collect()
+        }
+        else if line.starts_with("view ") {
+            self.views.iter().filter(|v| v.starts_with(partial)).cloned().collect()
+        }
+        else if line.starts_with("done") || line.starts_with("tag ") {
+            self.open.iter().map(|i| i.to_string()).filter(|i| i.starts_with(partial)).collect()
+        }
+        else {
+            Vec::new()
+        };
+        Ok((start, candidates.into_iter().map(|c| Pair{display: c.clone(), replacement: c}).collect()))
+    }
+}
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+impl Highlighter for ReplHelper {}
+impl Validator for ReplHelper {}
+impl Helper for ReplHelper {}
+
+pub fn run<T: TodoLister>(mut tl: T) -> io::Result<()> {
+    let mut rl: Editor<ReplHelper, DefaultHistory> = Editor::new().map_err(to_io_error)?;
+    rl.set_helper(Some(ReplHelper::default()));
+    let mut views = Views::new();
+    let mut session = SearchSession::new();
+    println!("Type \"help\" for a list of commands.");
+    loop {
+        let line = match rl.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue, //ctrl-c discards the current line
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(to_io_error(e)),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue
+        }
+        rl.add_history_entry(line).map_err(to_io_error)?;
+        match line {
+            "help" => println!("{}", HELP),
+            "quit" | "exit" => break,
+            _ => match runner::try_run_line_in_session(line, &mut tl, &mut views, &mut session) {
+                Ok(r) => {
+                    if let Some(helper) = rl.helper_mut() {
+                        helper.record(line, &r);
+                    }
+                    match &r {
+                        QueryResult::Found(items) => print!("{}", table(items)),
+                        _ => println!("{}", r),
+                    }
+                },
+                Err(e) => eprintln!("Error: {}", e),
+            },
+        }
+    }
+    Ok(())
+}
+
+//renders search results as a box-drawn table, padded by display width so wide characters line up
+fn table(items: &[TodoItem]) -> String {
+    let headings = ["index", "description", "tags"];
+    let rows: Vec<[String; 3]> = items.iter().map(|item| [
+        item.index.to_string(),
+        item.description.iter().map(Word::value).collect::<Vec<_>>().join(" "),
+        item.tags.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" "),
+    ]).collect();
+    let mut widths = headings.map(|h| h.width());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    let rule = |left: &str, mid: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        format!("{}{}{}\n", left, segments.join(mid), right)
+    };
+    let line = |cells: [&str; 3]| {
+        let padded: Vec<String> = cells.iter().zip(widths.iter())
+            .map(|(cell, w)| format!(" {}{} ", cell, " ".repeat(w - cell.width())))
+            .collect();
+        format!("│{}│\n", padded.join("│"))
+    };
+
+    let mut out = rule("┌", "┬", "┐");
+    out.push_str(&line(headings));
+    out.push_str(&rule("├", "┼", "┤"));
+    for [index, description, tags] in &rows {
+        out.push_str(&line([index.as_str(), description.as_str(), tags.as_str()]));
+    }
+    out.push_str(&rule("└", "┴", "┘"));
+    out.push_str(&format!("{} item(s) found\n", items.len()));
+    out
+}
+
+fn to_io_error(e: ReadlineError) -> io::Error {
+    match e {
+        ReadlineError::Io(e) => e,
+        e => io::Error::other(e),
+    }
+}
@@ -0,0 +1,97 @@
+extern crate todo_swamp;
+
+use todo_swamp::generator::{self, GeneratorConfig};
+
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+#[command(about = "Writes a reproducible random stream of queries, preceded by their count, for the application to read")]
+struct Options {
+    ///number of queries to write
+    #[arg(short = 'n', long, default_value_t = 10_000)]
+    count: usize,
+
+    ///file to write queries to [default: standard output]
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    ///seed for the random number generator, the same seed and options always give the same queries
+    #[arg(short, long, default_value_t = 0)]
+    seed: u64,
+
+    ///relative weight of add queries
+    #[arg(long, default_value_t = 5)]
+    adds: u32,
+
+    ///relative weight of done queries
+    #[arg(long, default_value_t = 1)]
+    dones: u32,
+
+    ///relative weight of search queries
+    #[arg(long, default_value_t = 4)]
+    searches: u32,
+
+    ///number of distinct words in descriptions
+    #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..))]
+    vocabulary: u32,
+
+    ///number of distinct tags
+    #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(u32).range(1..))]
+    tag_vocabulary: u32,
+
+    ///comma separated relative weights of word lengths, starting at one letter
+    #[arg(long, value_delimiter = ',', default_value = "1,3,8,12,12,10,8,5,3,2,1")]
+    word_lengths: Vec<u32>,
+
+    ///fewest and most words in a description
+    #[arg(long, num_args = 2, value_names = ["MIN", "MAX"], default_values_t = [1, 6])]
+    description_words: Vec<usize>,
+
+    ///fewest and most tags on an item
+    #[arg(long, num_args = 2, value_names = ["MIN", "MAX"], default_values_t = [0, 3])]
+    item_tags: Vec<usize>,
+
+    ///fewest and most terms in a search
+    #[arg(long, num_args = 2, value_names = ["MIN", "MAX"], default_values_t = [1, 3])]
+    search_terms: Vec<usize>,
+
+    ///chance of each search term being a tag rather than a word
+    #[arg(long, default_value_t = 0.25)]
+    tag_term_chance: f64,
+
+    ///chance of a search asking for each of a limit, an offset and a sort order
+    #[arg(long, default_value_t = 0.0)]
+    search_option_chance: f64,
+}
+
+fn main() -> io::Result<()> {
+    let opts = Options::parse();
+    let config = GeneratorConfig {
+        add_weight: opts.adds,
+        done_weight: opts.dones,
+        search_weight: opts.searches,
+        vocabulary: opts.vocabulary as usize,
+        tag_vocabulary: opts.tag_vocabulary as usize,
+        word_length_weights: opts.word_lengths,
+        description_words: (opts.description_words[0], opts.description_words[1]),
+        item_tags: (opts.item_tags[0], opts.item_tags[1]),
+        search_terms: (opts.search_terms[0], opts.search_terms[1]),
+        tag_term_chance: opts.tag_term_chance,
+        search_option_chance: opts.search_option_chance,
+        seed: opts.seed,
+    };
+    if let Err(e) = config.check() {
+        eprintln!("Could not generate queries: {}", e);
+        std::process::exit(1);
+    }
+    let mut out: Box<dyn Write> = match &opts.output {
+        Some(path) => Box::new(BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    generator::write_queries(config, opts.count, &mut out)?;
+    out.flush()
+}
@@ -0,0 +1,67 @@
+extern crate todo_swamp;
+
+use todo_swamp::*;
+use todo_swamp::sharded::ShardedTodoList;
+
+use clap::{CommandFactory, Parser, ValueEnum};
+use clap::error::ErrorKind;
+use tiny_http::Server;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Backend {
+    Naive,
+    Naive2,
+    Trie1,
+    Trie2,
+    Trie3,
+    Trie4,
+    Trie5,
+    Sharded,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "http_server", about = "Serves a todo list over a JSON REST API")]
+struct Options {
+    ///todo list implementation to answer requests with
+    #[arg(short, long, value_enum, default_value_t = Backend::Naive)]
+    backend: Backend,
+
+    ///address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    address: String,
+
+    ///number of shards the sharded backend searches in parallel, only with --backend sharded [default: one per cpu]
+    #[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u16).range(1..))]
+    shards: Option<u16>,
+}
+
+fn main() {
+    let opts = Options::parse();
+    if opts.shards.is_some() && opts.backend != Backend::Sharded {
+        Options::command().error(ErrorKind::ArgumentConflict, "--shards can only be used with --backend sharded").exit();
+    }
+    let server = match Server::http(&opts.address) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Could not listen on {}: {}", opts.address, e);
+            std::process::exit(1);
+        },
+    };
+    match opts.backend {
+        Backend::Naive => http::serve(server, TodoList::new()),
+        Backend::Naive2 => http::serve(server, TodoList2::new()),
+        Backend::Trie1 => http::serve(server, TriedoList::<Trie1>::new()),
+        Backend::Trie2 => http::serve(server, TriedoList::<Trie2>::new()),
+        Backend::Trie3 => http::serve(server, TriedoList::<Trie3>::new()),
+        Backend::Trie4 => http::serve(server, TriedoList::<Trie4>::new()),
+        Backend::Trie5 => http::serve(server, TriedoList::<Trie5>::new()),
+        Backend::Sharded => http::serve(server, sharded(opts.shards)),
+    }
+}
+
+fn sharded(shards: Option<u16>) -> ShardedTodoList {
+    match shards {
+        Some(n) => ShardedTodoList::with_shards(n.into()),
+        None => ShardedTodoList::new(),
+    }
+}
@@ -0,0 +1,205 @@
+//Remembers search results in front of any backend, for callers which keep repeating the same searches
+//Entries are dropped only when a change could alter them: a push drops the searches its item matches,
+//a done drops the searches whose results held that item, and a tag edit drops both kinds for its item
+//Once full, the search used longest ago is dropped to make room for a new one
+
+use crate::*;
+use crate::memory::{self, MemoryUsage};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rustc_hash::FxHashMap;
+
+//searches remembered before old entries start being dropped to make room
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+//shared, so counts can still be read once the list has been handed off to be served or run
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+impl CacheStats {
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} cache hits, {} cache misses", self.hits(), self.misses())
+    }
+}
+
+//results are kept as indices and looked back up on a hit, in the order the wrapped list first gave them,
+//each with the tick of the clock it was last used at
+#[derive(Debug, Default)]
+struct Entries {
+    found: FxHashMap<SearchParams, (Vec<Index>, u64)>,
+    //moves on with every search stored or answered, so the oldest tick marks the entry used longest ago
+    clock: u64,
+}
+impl Entries {
+    fn touch(&mut self, key: &SearchParams) -> Option<&Vec<Index>> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.found.get_mut(key).map(|(found, used)| {
+            *used = clock;
+            &*found
+        })
+    }
+
+    fn retain<F: FnMut(&SearchParams, &Vec<Index>) -> bool>(&mut self, mut keep: F) {
+        self.found.retain(|key, (found, _)| keep(key, found));
+    }
+}
+
+//(behind a mutex so that the list stays Sync, searches only hold it to look up or store an entry)
+#[derive(Debug)]
+pub struct CachedTodoList<T: TodoLister> {
+    inner: T,
+    results: Mutex<Entries>,
+    capacity: usize,
+    stats: Arc<CacheStats>,
+}
+impl<T: TodoLister> CachedTodoList<T> {
+    pub fn new(tl: T) -> Self {
+        Self::with_capacity(tl, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(tl: T, capacity: usize) -> Self {
+        CachedTodoList {
+            inner: tl,
+            results: Mutex::new(Entries::default()),
+            capacity,
+            stats: Arc::new(CacheStats::default()),
+        }
+    }
+
+    pub fn cache_stats(&self) -> Arc<CacheStats> {
+        Arc::clone(&self.stats)
+    }
+
+    //number of searches currently remembered
+    pub fn cached(&self) -> usize {
+        self.lock().found.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.lock().found.clear();
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    //the order and repetition of terms doesn't change what a search finds, so neither is part of the key,
+    //and every match is kept whatever page was asked for, since a push or done can move any page along
+    fn key(sp: &SearchParams) -> SearchParams {
+        let mut key = sp.normalised().without_options();
+        key.params.sort();
+        key.params.dedup();
+        key
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.results.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+impl<T: TodoLister + Default> Default for CachedTodoList<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+impl<T: TodoLister> TodoLister for CachedTodoList<T> {
+    fn push(&mut self, description: Vec<Word>, tags: Vec<Tag>) -> TodoItem {
+        let item = self.inner.push(description, tags);
+        self.lock().retain(|key, _| !todo_list::matches(&item, key));
+        item
+    }
+    fn done_with_index(&mut self, idx: Index) -> Option<Index> {
+        let done = self.inner.done_with_index(idx)?;
+        self.lock().retain(|_, found| !found.contains(&idx));
+        Some(done)
+    }
+    fn search(&self, sp: SearchParams) -> Vec<&TodoItem> {
+        let key = Self::key(&sp);
+        let sp = sp.normalised();
+        if let Some(found) = self.lock().touch(&key) {
+            self.stats.hits.fetch_add(1, Ordering::Relaxed);
+            return todo_list::arrange(found.iter().filter_map(|idx| self.inner.get(*idx)).collect(), &sp)
+        }
+        self.stats.misses.fetch_add(1, Ordering::Relaxed);
+        let results = self.inner.search(sp.without_options());
+        let mut cache = self.lock();
+        if cache.found.len() >= self.capacity { //make room by dropping the entry used longest ago
+            if let Some(old) = cache.found.iter().min_by_key(|(_, (_, used))| *used).map(|(key, _)| key.clone()) {
+                cache.found.remove(&old);
+            }
+        }
+        if self.capacity > 0 {
+            cache.clock += 1;
+            let clock = cache.clock;
+            cache.found.insert(key, (results.iter().map(|item| item.index).collect(), clock));
+        }
+        todo_list::arrange(results, &sp)
+    }
+    fn get(&self, idx: Index) -> Option<&TodoItem> {
+        self.inner.get(idx)
+    }
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+    fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+    //counts are answered from remembered searches, but not remembered themselves, as the wrapped list may count without searching
+    fn count(&self, sp: SearchParams) -> usize {
+        if let Some(found) = self.lock().touch(&Self::key(&sp)) {
+            self.stats.hits.fetch_add(1, Ordering::Relaxed);
+            return sp.options.page_len(found.len())
+        }
+        self.stats.misses.fetch_add(1, Ordering::Relaxed);
+        self.inner.count(sp)
+    }
+    fn stats(&self) -> ListStats {
+        self.inner.stats()
+    }
+    fn tags(&self, prefix: &str) -> BTreeMap<String, usize> {
+        self.inner.tags(prefix)
+    }
+    fn edit_tags(&mut self, idx: Index, edit: &TagEdit) -> Option<bool> {
+        let changed = self.inner.edit_tags(idx, edit)?;
+        if changed {
+            if let Some(item) = self.inner.get(idx) {
+                self.lock().retain(|key, found| !found.contains(&idx) && !todo_list::matches(item, key));
+            }
+        }
+        Some(changed)
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        let cache = self.lock();
+        let keys: usize = cache.found.keys().map(|key| {
+            memory::vec_bytes(&key.params) + key.params.iter().map(|param| match param {
+                SearchWordOrTag::RawWord(s) | SearchWordOrTag::RawTag(s) => s.capacity(),
+            }).sum::<usize>()
+        }).sum();
+        self.inner.memory_usage() + MemoryUsage {
+            maps: memory::hash_map_bytes(&cache.found),
+            id_sets: cache.found.values().map(|(found, _)| memory::vec_bytes(found)).sum(),
+            other: keys,
+            ..MemoryUsage::default()
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//Autocomplete: ways to finish a partly typed word or #tag, taken from the open items in a list
+//Tags come from the list's tag counts, words from searching for the partial word, so both use whatever index the backend has
+
+use crate::*;
+
+use rustc_hash::FxHashMap;
+
+//at most max suggestions, the ones on the most open items first (then alphabetically), all starting with the partial text
+//(suggestions are normalised, as words and tags are matched on their normalised keys)
+pub fn suggestions<T: TodoLister>(tl: &T, partial: &str, max: usize) -> Vec<String> {
+    let mut counted: Vec<(String, usize)> = match partial.strip_prefix('#') {
+        Some(partial_tag) => {
+            tl.tags(&normalise(partial_tag)).into_iter().filter(|(_, open)| *open > 0).map(|(tag, open)| (format!("#{}", tag), open)).collect()
+        },
+        None => words(tl, &normalise(partial)).into_iter().collect(),
+    };
+    counted.sort_unstable_by(|(a, a_open), (b, b_open)| b_open.cmp(a_open).then_with(|| a.cmp(b)));
+    counted.into_iter().take(max).map(|(suggestion, _)| suggestion).collect()
+}
+
+//open items with a word starting with a normalised prefix, counted by word
+//(searching for the prefix finds every such item, along with others which only have its letters spread out)
+fn words<T: TodoLister>(tl: &T, prefix: &str) -> FxHashMap<String, usize> {
+    let mut counts = FxHashMap::default();
+    if prefix.is_empty() {
+        return counts
+    }
+    for item in tl.search(SearchParams::new(vec![SearchWordOrTag::RawWord(prefix.to_owned())])) {
+        let mut keys: Vec<&str> = item.description.iter().map(Word::key).filter(|key| key.starts_with(prefix)).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        for key in keys {
+            *counts.entry(key.to_owned()).or_default() += 1;
+        }
+    }
+    counts
+}
@@ -0,0 +1,132 @@
+//Lets one todo list be shared between threads: any number of searches run in parallel,
+//while pushes, dones and tag edits wait for exclusive access
+//Tag edits are counted, so that sessions searching the list can tell when one may have made an item they passed over match
+//The searches saved with the list are kept behind a lock of their own, always taken after the list's
+
+use crate::*;
+use crate::cache::CachedTodoList;
+use crate::observer::ObservedTodoList;
+use crate::session::SearchSession;
+use crate::sharded::ShardedTodoList;
+use crate::views::Views;
+
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Default)]
+pub struct ConcurrentTodoList<T: TodoLister + Send + Sync> {
+    inner: RwLock<T>,
+    //only changed with the exclusive lock held, so it can't move on while a search holds the shared one
+    tag_edits: AtomicU64,
+    views: RwLock<Views>,
+}
+impl<T: TodoLister + Send + Sync> ConcurrentTodoList<T> {
+    pub fn new(tl: T) -> Self {
+        ConcurrentTodoList {
+            inner: RwLock::new(tl),
+            tag_edits: AtomicU64::new(0),
+            views: RwLock::new(Views::new()),
+        }
+    }
+
+    pub fn push(&self, description: Vec<Word>, tags: Vec<Tag>) -> TodoItem {
+        self.write().push(description, tags)
+    }
+
+    pub fn done_with_index(&self, idx: Index) -> Option<Index> {
+        self.write().done_with_index(idx)
+    }
+
+    //an edit which finds no item or changes nothing leaves sessions' results as they were, so isn't counted
+    pub fn edit_tags(&self, idx: Index, edit: &TagEdit) -> Option<bool> {
+        let mut tl = self.write();
+        let edited = tl.edit_tags(idx, edit);
+        if edited == Some(true) {
+            self.tag_edits.fetch_add(1, Ordering::Relaxed);
+        }
+        edited
+    }
+
+    //results are cloned out, since they can't borrow from the list once the lock is released
+    //(hold read() instead to work with references)
+    pub fn search(&self, sp: SearchParams) -> Vec<TodoItem> {
+        self.read().search(sp).into_iter().cloned().collect()
+    }
+
+    pub fn get(&self, idx: Index) -> Option<TodoItem> {
+        self.read().get(idx).cloned()
+    }
+
+    //as runner::try_run_line, only taking the exclusive lock for queries which change the list
+    //(held for the whole of a bulk query, so no other thread sees it half done)
+    pub fn run_line(&self, line: &str) -> Result<QueryResult, QueryError> {
+        match runner::parse_line(line)? {
+            q if runner::changes_list(&q) => self.run_change(q),
+            q => self.run_read_only(q),
+        }
+    }
+
+    //as run_line, with searches refining the session's previous results where they can
+    //(as long as no tag has been edited since, from this session or any other)
+    pub fn run_line_in_session(&self, line: &str, session: &mut SearchSession) -> Result<QueryResult, QueryError> {
+        match runner::parse_line(line)? {
+            Query::Search(params) => {
+                let tl = self.read();
+                session.catch_up(self.tag_edits.load(Ordering::Relaxed));
+                runner::run_search_in_session(params, &*tl, &self.views(), session)
+            },
+            q if runner::changes_list(&q) => self.run_change(q),
+            q => self.run_read_only(q),
+        }
+    }
+
+    fn run_read_only(&self, q: Query) -> Result<QueryResult, QueryError> {
+        let tl = self.read();
+        runner::run_read_only(q, &*tl, &self.views())
+    }
+
+    fn run_change(&self, q: Query) -> Result<QueryResult, QueryError> {
+        let mut tl = self.write();
+        let edits_tags = runner::edits_tags(&q);
+        let result = runner::run_query(q, &mut *tl, &mut self.views.write().unwrap_or_else(PoisonError::into_inner));
+        if edits_tags && matches!(result, Ok(QueryResult::Edited(_)) | Ok(QueryResult::Affected(1..))) {
+            self.tag_edits.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    //keep going even if another thread panicked while holding the lock, rather than failing every later caller too
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    //tag edits made through the guard aren't counted, so sessions don't hear of them, edit through edit_tags or run_line instead
+    pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    //the searches saved with the list, to be locked after it when both are held
+    pub fn views(&self) -> RwLockReadGuard<'_, Views> {
+        self.views.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+//every backend has to stay shareable, this stops compiling if one of them loses Send or Sync
+#[allow(dead_code)]
+fn assert_send_sync() {
+    fn check<T: Send + Sync>() {}
+    check::<ConcurrentTodoList<TodoList>>();
+    check::<ConcurrentTodoList<TodoList2>>();
+    check::<ConcurrentTodoList<TriedoList<Trie1>>>();
+    check::<ConcurrentTodoList<TriedoList<Trie2>>>();
+    check::<ConcurrentTodoList<TriedoList<Trie3>>>();
+    check::<ConcurrentTodoList<TriedoList<Trie4>>>();
+    check::<ConcurrentTodoList<TriedoList<Trie5>>>();
+    check::<ConcurrentTodoList<ShardedTodoList>>();
+    check::<ConcurrentTodoList<CachedTodoList<TriedoList<Trie5>>>>();
+    check::<ConcurrentTodoList<ObservedTodoList<TodoList>>>();
+}
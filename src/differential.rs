@@ -0,0 +1,129 @@
+//Runs every query through all the backends side by side, and reports the first query they don't all answer the same way
+//The tries find items in no particular order, so found items are sorted by index before answers are compared,
+//unless the search asked for them sorted
+//The backends share one set of saved searches, as they'd be kept next to any one list
+
+use crate::*;
+use crate::cache::CachedTodoList;
+use crate::observer::ObservedTodoList;
+use crate::sharded::ShardedTodoList;
+use crate::views::Views;
+
+use std::fmt;
+
+pub type Answer = Result<QueryResult, QueryError>;
+
+//the query is boxed to keep results carrying a mismatch small, since queries hold whole searches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub query: Box<Query>,
+    pub answers: Vec<(&'static str, Answer)>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "backends disagree on {:?}", self.query)?;
+        for (name, answer) in &self.answers {
+            match answer {
+                Ok(r) => write!(f, "\n{}: {}", name, r.to_string().replace('\n', " | "))?,
+                Err(e) => write!(f, "\n{}: {}", name, e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Differential {
+    naive: TodoList,
+    naive2: TodoList2,
+    trie1: TriedoList<Trie1>,
+    trie2: TriedoList<Trie2>,
+    trie3: TriedoList<Trie3>,
+    trie4: TriedoList<Trie4>,
+    trie5: TriedoList<Trie5>,
+    sharded: ShardedTodoList,
+    cached: CachedTodoList<TodoList>,
+    observed: ObservedTodoList<TriedoList<Trie5>>,
+    views: Views,
+}
+impl Differential {
+    pub fn new() -> Self {
+        Differential {
+            naive: TodoList::new(),
+            naive2: TodoList2::new(),
+            trie1: TriedoList::new(),
+            trie2: TriedoList::new(),
+            trie3: TriedoList::new(),
+            trie4: TriedoList::new(),
+            trie5: TriedoList::new(),
+            sharded: ShardedTodoList::with_shards(3),
+            cached: CachedTodoList::new(TodoList::new()),
+            observed: ObservedTodoList::new(TriedoList::new()),
+            views: Views::new(),
+        }
+    }
+
+    //lines which don't parse are answered with the parse error, without reaching the backends
+    pub fn run_line(&mut self, line: &str) -> Result<Answer, Mismatch> {
+        match runner::parse_line(line) {
+            Ok(q) => self.run_query(q),
+            Err(e) => Ok(Err(e)),
+        }
+    }
+
+    //answers each line in turn, stopping at the first mismatch, and otherwise giving back how many lines were run
+    pub fn run_lines<'a, I: IntoIterator<Item = &'a str>>(&mut self, lines: I) -> Result<usize, Mismatch> {
+        let mut count = 0;
+        for line in lines {
+            let _ = self.run_line(line)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    //every backend runs the query with the saved searches as they were before it, and what the first one saves is kept
+    pub fn run_query(&mut self, q: Query) -> Result<Answer, Mismatch> {
+        let mut views = self.views.clone();
+        let answers = vec![
+            ("naive", answer(&q, &mut self.naive, &mut views)),
+            ("naive2", answer(&q, &mut self.naive2, &mut self.views.clone())),
+            ("trie1", answer(&q, &mut self.trie1, &mut self.views.clone())),
+            ("trie2", answer(&q, &mut self.trie2, &mut self.views.clone())),
+            ("trie3", answer(&q, &mut self.trie3, &mut self.views.clone())),
+            ("trie4", answer(&q, &mut self.trie4, &mut self.views.clone())),
+            ("trie5", answer(&q, &mut self.trie5, &mut self.views.clone())),
+            ("sharded", answer(&q, &mut self.sharded, &mut self.views.clone())),
+            ("cached", answer(&q, &mut self.cached, &mut self.views.clone())),
+            ("observed", answer(&q, &mut self.observed, &mut self.views.clone())),
+        ];
+        self.views = views;
+        if answers.iter().all(|(_, a)| *a == answers[0].1) {
+            Ok(answers.into_iter().next().unwrap().1)
+        }
+        else {
+            Err(Mismatch{query: Box::new(q), answers})
+        }
+    }
+}
+impl Default for Differential {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn answer<T: TodoLister>(q: &Query, tl: &mut T, views: &mut Views) -> Answer {
+    //whether results come sorted can depend on the options of the saved searches a search names
+    let sp = match q {
+        Query::Search(sp) => views.expand(sp).ok(),
+        Query::View(name) => views.get(name).cloned(),
+        _ => None,
+    };
+    let mut answer = runner::run_query(q.clone(), tl, views);
+    if let (Some(sp), Ok(QueryResult::Found(items))) = (sp, &mut answer) {
+        if sp.options.order().is_none() { //sorted searches have to agree on the order too
+            items.sort_by_key(|item| item.index);
+        }
+    }
+    answer
+}
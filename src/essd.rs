@@ -1,5 +1,6 @@
 //Implementation of Trie algorithm from "Efficient Subsequence Search for Databases"
 //https://link.springer.com/chapter/10.1007/978-3-642-38562-9_45
+//(unfinished, nothing outside this module uses it yet, so the items it hasn't got round to using are allowed to go unused)
 
 //Assertions:
 //1: Max trie depth and hence maximum searchable attribute (string) length is static (set by ATTR_MAX)
@@ -10,23 +11,32 @@ use std::collections::HashSet;
 use arrayvec::ArrayVec;
 use std::ptr::NonNull;
 
+#[allow(dead_code)]
 const CARDINALITY: usize = 3;   //TERMINOLOGY K: number of symbols in alphabet (does not include special 'Root' symbol)
 const ATTR_MAX: usize = 10;     //TERMINOLOGY M: max length of attributes
 
+#[allow(dead_code)]
 #[derive(PartialEq, Eq, Clone, Copy, Hash)]
 struct Id(usize);
+#[allow(dead_code)]
 #[derive(PartialEq, Eq, Clone, Copy)]
 struct Lvl(usize);
+#[allow(dead_code)]
 #[derive(PartialEq, Eq, Clone, Copy)]
 struct Pos(usize);
 
+#[allow(dead_code)]
 const EMPTY_LEVEL: Vec<Node> = Vec::new(); //needed for array initialization
 
+#[allow(dead_code)]
 struct Link<T>(T, Option<NonNull<Link<T>>>);
+#[allow(dead_code)]
 type LinkedList<T> = Vec<Link<T>>; //hacky way of getting linked-list-like functionality
 
+#[allow(dead_code)]
 type Trie = [Vec<Node>; ATTR_MAX + 1];
 
+#[allow(dead_code)]
 #[derive(Clone)]
 enum Sigma { //TERMINOLOGY Σ: alphabet of symbols
     A, B, C, Root,
@@ -83,11 +93,13 @@ impl From<SigString> for String {
     }
 }
 
+#[allow(dead_code)]
 struct Essd { 
     table: Vec<SigString>, //id = vector index (we never need to delete items from the table, only make them unsearchable through the trie)
     trie: Trie,
     ids: LinkedList<Id>, 
 }
+#[allow(dead_code)]
 impl Essd {
     fn table_length(&self) -> usize { //TERMINOLOGY N: number of tuples in table
         self.table.len()
@@ -99,6 +111,7 @@ impl Essd {
         self.node(Pos(0), Lvl(0)).unwrap()
     }
 }
+#[allow(dead_code)]
 impl Essd {
     fn new() -> Self {
         let mut trie = [EMPTY_LEVEL; ATTR_MAX + 1];
@@ -110,7 +123,7 @@ impl Essd {
             ids: Vec::new(),
         }
     }
-    fn insert(&mut self, attribute: SigString) { //No id necessary here since Essd.table: Vec<SigString>
+    fn insert(&mut self, _attribute: SigString) { //No id necessary here since Essd.table: Vec<SigString>
         unimplemented!()
     }
     fn search(&self, SigString(query): SigString) -> Vec<(Id, SigString)> {
@@ -119,11 +132,12 @@ impl Essd {
         let ids = self.root().search(&query);
         ids.into_iter().map(|id| (id, self.table[id.0].to_owned())).collect()
     }
-    fn delete(&mut self, id: Id) {
+    fn delete(&mut self, _id: Id) {
         unimplemented!()
     }
 }
 
+#[allow(dead_code, clippy::type_complexity)]
 struct Node {
     label: Sigma,
 
@@ -137,6 +151,7 @@ struct Node {
     next: Option<NonNull<Node>>,
     parent: Option<NonNull<Node>>,
 }
+#[allow(dead_code)]
 impl Node {
     fn new(
         label: Sigma,
@@ -154,10 +169,10 @@ impl Node {
             parent,
         }
     }
-    fn get_child<'a>(&self, symbol: Sigma) -> Option<&Node> {
+    fn get_child(&self, symbol: Sigma) -> Option<&Node> {
         self.fresh[0][usize::from(symbol)].map(|(first, _)| unsafe { &*first.as_ptr() })
     }
-    fn insert(&mut self, id: Id, attribute: &[Sigma]) { //does not support update (i.e. id should not already exist)
+    fn insert(&mut self, _id: Id, _attribute: &[Sigma]) { //does not support update (i.e. id should not already exist)
         unimplemented!()
         /*
         push id onto the end of linked-ids, once we reach the end of the "chain" it should be apparent what to set the indices to
@@ -205,7 +220,7 @@ impl Node {
     }
     fn search(&self, query: &[Sigma]) -> HashSet<Id> {
         let mut tuples = HashSet::new();
-        if query.len() == 0 {
+        if query.is_empty() {
             return self.tuples_in_subtree();
         }
         for fresh_level in self.fresh.iter() {
@@ -234,7 +249,7 @@ impl Node {
         }
         tuples
     }
-    fn delete(&mut self, id: Id) {
+    fn delete(&mut self, _id: Id) {
         unimplemented!()
     }
 }
\ No newline at end of file
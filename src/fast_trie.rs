@@ -1,23 +1,29 @@
 //Experimental implementation of modified essd algorithm to handle duplicate words, 
 //multiple words per ID, unbounded word length (subject to available memory) and tree pruning similar to the methods used in trie.rs
+//(unfinished, nothing outside this module uses it yet, so the items it hasn't got round to using are allowed to go unused)
 
 use std::collections::HashSet;
 use std::iter;
 use std::slice;
 use std::str;
 
+#[allow(dead_code)]
 const CARDINALITY: usize = 27;
 
+#[allow(dead_code)]
 #[derive(PartialEq, Eq, Clone, Copy, Hash)]
 struct Id(u64);
 
+#[allow(dead_code)]
 #[derive(PartialEq, Eq, Clone, Copy)]
 struct Index(usize);
 
+#[allow(dead_code)]
 struct FastIterMutParent<'a> {
     level_iter: iter::Rev<iter::Take<slice::IterMut<'a, Vec<Node>>>>,
     position: Option<Index>,
 }
+#[allow(dead_code)]
 impl<'a> FastIterMutParent<'a> {
     fn new(fast: &'a mut Fast, level: Index, position: Index) -> Self {
         FastIterMutParent {
@@ -36,11 +42,13 @@ impl<'a> Iterator for FastIterMutParent<'a> {
     }
 }
 
+#[allow(dead_code)]
 struct FastIterMutChild<'a, 'b> {
     level_iter: iter::Take<slice::IterMut<'a, Vec<Node>>>,
     position: Option<Index>,
     char_iter: str::Chars<'b>,
 }
+#[allow(dead_code)]
 impl<'a, 'b> FastIterMutChild<'a, 'b> {
     fn new(fast: &'a mut Fast, word: &'b str) -> Self {
         FastIterMutChild {
@@ -60,8 +68,10 @@ impl<'a, 'b> Iterator for FastIterMutChild<'a, 'b> {
     }
 }
 
+#[allow(dead_code)]
 struct Fast(Vec<Vec<Node>>);
 
+#[allow(dead_code)]
 impl Fast {
     pub fn new() -> Self {
         Fast(vec![vec![Node::root()]])
@@ -83,6 +93,7 @@ impl Fast {
     }
 }
 
+#[allow(dead_code)]
 impl Fast {
     fn iter_mut_parent(&mut self, level: Index, position: Index) -> FastIterMutParent<'_> {
         FastIterMutParent::new(self, level, position)
@@ -116,19 +127,20 @@ fn insert_single(&mut self, id: Id, attribute: &str)
             if not, you can stop without going further 
 */
 
+#[allow(dead_code)]
 impl Fast {
     fn insert_single(&mut self, id: Id, attribute: &str) {
         //ensure we have enough levels to insert into
         let diff = attribute.len() - (self.0.len() - 1);
-        self.0.append(&mut iter::repeat(Vec::new()).take(diff).collect());
+        self.0.append(&mut iter::repeat_n(Vec::new(), diff).collect());
 
         if let Some((last_level, last_position)) = self.add_ids(id, attribute) { //iterate down children, adding ids
 
             self.add_nodes(id, attribute, last_level, last_position); //add all remaining nodes, with correct parent
 
             //iterate up parents from bottom node, correcting "fresh" and "next" indices
-            let mut parent_iter = self.iter_mut_parent(Index(attribute.len()), Index(self.0[attribute.len()].len()-1));
-            for (i, parent) in parent_iter.enumerate() {
+            let parent_iter = self.iter_mut_parent(Index(attribute.len()), Index(self.0[attribute.len()].len()-1));
+            for (i, _parent) in parent_iter.enumerate() {
                 if i < attribute.len() - last_level.0 {
                     //parent is one of the newly added nodes
                     
@@ -140,11 +152,11 @@ impl Fast {
             }
         }
     }
-    fn search_single(&self, attribute: &str) -> HashSet<Id> {
+    fn search_single(&self, _attribute: &str) -> HashSet<Id> {
         unimplemented!()
     }
-    fn delete_at_positions(mut levels: slice::IterMut<Vec<Node>>, mut positions: Vec<Index>, id: Id) {
-        while let Some(level) = levels.next() {
+    fn delete_at_positions(levels: slice::IterMut<Vec<Node>>, mut positions: Vec<Index>, id: Id) {
+        for level in levels {
             if positions.is_empty() {
                 break
             }
@@ -160,6 +172,7 @@ impl Fast {
     }
 }
 
+#[allow(dead_code)]
 impl Fast {
     fn add_ids(&mut self, id: Id, attribute: &str) -> Option<(Index, Index)> {
         let mut child_iter = self.iter_mut_child(attribute);
@@ -185,6 +198,7 @@ impl Fast {
     }
 }
 
+#[allow(dead_code)]
 #[derive(Clone)]
 struct Node {
     ids: HashSet<Id>,
@@ -195,6 +209,7 @@ struct Node {
     next: Option<Index>,
     parent: Option<Index>,
 }
+#[allow(dead_code)]
 impl Node {
     fn root() -> Self {
         Node {
@@ -220,16 +235,17 @@ impl Node {
     }
     fn child_positions(&self) -> Vec<Index> {
         self.fresh
-            .get(0).unwrap_or(&[None; CARDINALITY])
+            .first().unwrap_or(&[None; CARDINALITY])
             .iter().filter_map(|e| e.map(|(f,_)| f)).collect()
     }
     fn child_position(&self, label: char) -> Option<Index> {
         self.fresh
-            .get(0)
+            .first()
             .and_then(|arr| arr[char_to_index(label)].map(|(f,_)| f))
     }
 }
 
+#[allow(dead_code)]
 fn char_to_index(c: char) -> usize {
     match c {
         'a'..='z' => c as usize - 'a' as usize,
@@ -0,0 +1,204 @@
+//Generates reproducible streams of random queries, written in the same syntax the parser reads
+//Words are drawn from a fixed vocabulary of random lowercase words, so searches (subsequences of vocabulary words)
+//find something, and dones mostly name items which have already been added
+
+use std::io::{self, Write};
+
+use rand::{rngs::StdRng, seq::SliceRandom, distributions::WeightedIndex, prelude::Distribution, Rng, SeedableRng};
+use rustc_hash::FxHashSet;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorConfig {
+    //relative weights of each kind of query
+    pub add_weight: u32,
+    pub done_weight: u32,
+    pub search_weight: u32,
+    //number of distinct words and tags to draw from
+    pub vocabulary: usize,
+    pub tag_vocabulary: usize,
+    //weight of each word length, starting at one letter
+    pub word_length_weights: Vec<u32>,
+    //inclusive bounds on the words in a description, tags on an item and terms in a search
+    pub description_words: (usize, usize),
+    pub item_tags: (usize, usize),
+    pub search_terms: (usize, usize),
+    //chance of each search term being a tag rather than a word
+    pub tag_term_chance: f64,
+    //chance of a search asking for each of a limit, an offset and a sort order
+    pub search_option_chance: f64,
+    pub seed: u64,
+}
+impl GeneratorConfig {
+    pub fn new() -> Self {
+        GeneratorConfig {
+            add_weight: 5,
+            done_weight: 1,
+            search_weight: 4,
+            vocabulary: 1000,
+            tag_vocabulary: 50,
+            word_length_weights: vec![1, 3, 8, 12, 12, 10, 8, 5, 3, 2, 1],
+            description_words: (1, 6),
+            item_tags: (0, 3),
+            search_terms: (1, 3),
+            tag_term_chance: 0.25,
+            search_option_chance: 0.0,
+            seed: 0,
+        }
+    }
+
+    //why a generator couldn't be made with this config, if it couldn't
+    pub fn check(&self) -> Result<(), String> {
+        if self.add_weight == 0 && self.done_weight == 0 && self.search_weight == 0 {
+            return Err(String::from("at least one kind of query needs a positive weight"))
+        }
+        if self.word_length_weights.iter().all(|w| *w == 0) {
+            return Err(String::from("at least one word length needs a positive weight"))
+        }
+        if self.vocabulary == 0 || self.tag_vocabulary == 0 {
+            return Err(String::from("vocabularies need at least one word"))
+        }
+        if self.description_words.0 == 0 || self.search_terms.0 == 0 {
+            return Err(String::from("descriptions and searches need at least one word"))
+        }
+        for (low, high) in &[self.description_words, self.item_tags, self.search_terms] {
+            if low > high {
+                return Err(format!("lower bound {} is above upper bound {}", low, high))
+            }
+        }
+        for (name, chance) in &[("tag term", self.tag_term_chance), ("search option", self.search_option_chance)] {
+            if !(0.0..=1.0).contains(chance) {
+                return Err(format!("{} chance {} is not between 0 and 1", name, chance))
+            }
+        }
+        Ok(())
+    }
+}
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//an endless stream of query lines
+#[derive(Debug, Clone)]
+pub struct Generator {
+    config: GeneratorConfig,
+    rng: StdRng,
+    query_kinds: WeightedIndex<u32>,
+    words: Vec<String>,
+    tags: Vec<String>,
+    added: u64,
+}
+impl Generator {
+    //panics if config.check() fails
+    pub fn new(config: GeneratorConfig) -> Self {
+        if let Err(e) = config.check() {
+            panic!("invalid generator config: {}", e);
+        }
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let query_kinds = WeightedIndex::new([config.add_weight, config.done_weight, config.search_weight]).unwrap();
+        let lengths = WeightedIndex::new(&config.word_length_weights).unwrap();
+        let words = vocabulary(&mut rng, &lengths, config.vocabulary);
+        let tags = vocabulary(&mut rng, &lengths, config.tag_vocabulary);
+        Generator {
+            config,
+            rng,
+            query_kinds,
+            words,
+            tags,
+            added: 0,
+        }
+    }
+
+    fn add(&mut self) -> String {
+        let Generator{config, rng, words, tags, ..} = self;
+        let (low, high) = config.description_words;
+        let description: Vec<&str> = (0..rng.gen_range(low, high + 1)).map(|_| &words.choose(rng).unwrap()[..]).collect();
+        let (low, high) = config.item_tags;
+        let tag_count = rng.gen_range(low, high + 1).min(tags.len());
+        let tags: Vec<String> = tags.choose_multiple(rng, tag_count).map(|t| format!(" #{}", t)).collect();
+        let line = format!("add \"{}\"{}", description.join(" "), tags.concat());
+        self.added += 1;
+        line
+    }
+
+    //one in every few dones names an item which was never added
+    fn done(&mut self) -> String {
+        format!("done {}", self.rng.gen_range(0, self.added + self.added / 10 + 1))
+    }
+
+    fn search(&mut self) -> String {
+        let Generator{config, rng, words, tags, ..} = self;
+        let (low, high) = config.search_terms;
+        let terms: Vec<String> = (0..rng.gen_range(low, high + 1)).map(|_| {
+            if rng.gen_bool(config.tag_term_chance) {
+                let tag = tags.choose(rng).unwrap();
+                format!("#{}", subsequence(rng, tag))
+            }
+            else {
+                let word = words.choose(rng).unwrap();
+                subsequence(rng, word)
+            }
+        }).collect();
+        let mut line = format!("search {}", terms.join(" "));
+        if config.search_option_chance > 0.0 { //drawing nothing otherwise, so streams without options stay the same
+            if rng.gen_bool(config.search_option_chance) {
+                line.push_str(&format!(" limit:{}", rng.gen_range(0, 20)));
+            }
+            if rng.gen_bool(config.search_option_chance) {
+                line.push_str(&format!(" offset:{}", rng.gen_range(0, 10)));
+            }
+            if rng.gen_bool(config.search_option_chance) {
+                line.push_str(&format!(" sort:{}", ["index", "-index", "score"].choose(rng).unwrap()));
+            }
+        }
+        line
+    }
+}
+impl Iterator for Generator {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        Some(match self.query_kinds.sample(&mut self.rng) {
+            0 => self.add(),
+            1 if self.added > 0 => self.done(),
+            1 => self.add(), //nothing to be done yet
+            _ => self.search(),
+        })
+    }
+}
+
+//writes a query count line followed by that many queries, as the application reads them
+pub fn write_queries<W: Write>(config: GeneratorConfig, count: usize, out: &mut W) -> io::Result<()> {
+    let generator = Generator::new(config);
+    writeln!(out, "{}", count)?;
+    for line in generator.take(count) {
+        writeln!(out, "{}", line)?;
+    }
+    Ok(())
+}
+
+//distinct random lowercase words, as many as can be found if the lengths allowed don't leave room for that many
+fn vocabulary(rng: &mut StdRng, lengths: &WeightedIndex<u32>, size: usize) -> Vec<String> {
+    let mut seen = FxHashSet::default();
+    let mut words = Vec::with_capacity(size);
+    for _ in 0..size * 10 {
+        if words.len() == size {
+            break
+        }
+        let word: String = (0..=lengths.sample(rng)).map(|_| rng.gen_range(b'a', b'z' + 1) as char).collect();
+        if seen.insert(word.clone()) {
+            words.push(word);
+        }
+    }
+    words
+}
+
+//a random non-empty subsequence of a word, such as a person might type to find it
+fn subsequence(rng: &mut StdRng, word: &str) -> String {
+    let chars: Vec<char> = word.chars().collect();
+    let len = rng.gen_range(1, chars.len() + 1);
+    let mut picked: Vec<usize> = rand::seq::index::sample(rng, chars.len(), len).into_vec();
+    picked.sort_unstable();
+    picked.into_iter().map(|i| chars[i]).collect()
+}
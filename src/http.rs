@@ -0,0 +1,230 @@
+//REST API over a todo list, answering in JSON:
+//  POST /items                 body {"description": ["buy", "milk"], "tags": ["shopping"]}, answers 201 with the added item
+//  POST /items/{index}/done    answers with the item, now done
+//  PATCH /items/{index}/tags   body {"add": ["urgent"], "remove": ["home"]} (either can be left out), answers with the edited item
+//  GET  /items/{index}         answers with the item
+//  GET  /search?q=<terms>      terms as they would follow "search " in a query (limit:, offset:, sort: and @<name> included),
+//                              answers with a list of the matching items
+//  PUT  /views/{name}?q=<terms>  saves a search under a name, answers {"name": "<name>", "search": "<terms>"}
+//  GET  /views/{name}          answers with a list of the items the saved search finds
+//  GET  /count?q=<terms>       answers {"count": <number of items the search would list>}
+//  GET  /stats                 answers {"items": {"open": 2, "done": 1}, "tags": {"<tag>": {"open": 1, "done": 0}}}
+//  GET  /tags?prefix=<prefix>  answers {"<tag>": <open items>} for every tag, or those starting with the optional prefix
+//  GET  /complete?q=<partial>  answers up to 10 ways to finish a partly typed word or #tag, as ["<word>"] or ["#<tag>"]
+//Items are {"index": 0, "description": ["buy", "milk"], "tags": ["shopping"], "done": false},
+//and failed requests are answered with a 4xx status and {"error": "<reason>"}, 413 for bodies over 64 KiB
+//(or 507 for adding to a list which is full).
+//Requests are answered one at a time, in the order they arrive.
+//Saved searches live for as long as the server does, next to the list it serves.
+
+use crate::*;
+use crate::views::Views;
+
+use std::io::Read;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+const MAX_SUGGESTIONS: usize = 10;
+
+#[derive(Deserialize)]
+struct NewItem {
+    description: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TagChanges {
+    #[serde(default)]
+    add: Vec<String>,
+    #[serde(default)]
+    remove: Vec<String>,
+}
+
+//answers requests until the server is unblocked
+pub fn serve<T: TodoLister>(server: Server, mut tl: T) {
+    let mut views = Views::new();
+    for mut request in server.incoming_requests() {
+        let (status, body) = match read_body(&mut request) {
+            Ok(body) => respond(request.method(), request.url(), &body, &mut tl, &mut views),
+            Err(response) => response,
+        };
+        let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let response = Response::from_string(body.to_string()).with_status_code(status).with_header(content_type);
+        if let Err(e) = request.respond(response) {
+            eprintln!("Could not send response: {}", e);
+        }
+    }
+}
+
+//bodies over the limit are refused rather than cut short, going by their Content-Length if they give one,
+//and otherwise by reading a byte past the limit
+fn read_body(request: &mut Request) -> Result<String, (u16, Value)> {
+    let too_large = || (413, error(&format!("request bodies are limited to {} bytes", MAX_BODY_BYTES)));
+    if request.body_length().is_some_and(|length| length as u64 > MAX_BODY_BYTES) {
+        return Err(too_large())
+    }
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().take(MAX_BODY_BYTES + 1).read_to_string(&mut body) {
+        return Err((400, error(&format!("could not read request body: {}", e))))
+    }
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(too_large())
+    }
+    Ok(body)
+}
+
+//the status code and json body answering a request
+fn respond<T: TodoLister>(method: &Method, url: &str, body: &str, tl: &mut T, views: &mut Views) -> (u16, Value) {
+    let (path, query_string) = match url.find('?') {
+        Some(i) => (&url[..i], &url[i + 1..]),
+        None => (url, ""),
+    };
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match (method, segments.as_slice()) {
+        (Method::Post, ["items"]) => add(body, tl),
+        (Method::Post, ["items", index, "done"]) => match parse_index(index) {
+            Some(idx) => match tl.done_with_index(idx) {
+                Some(idx) => (200, json!(tl.get(idx))),
+                None => not_found(idx),
+            },
+            None => (400, error("item indices are non-negative integers")),
+        },
+        (Method::Patch, ["items", index, "tags"]) => match parse_index(index) {
+            Some(idx) => edit_tags(idx, body, tl),
+            None => (400, error("item indices are non-negative integers")),
+        },
+        (Method::Get, ["items", index]) => match parse_index(index) {
+            Some(idx) => match tl.get(idx) {
+                Some(item) => (200, json!(item)),
+                None => not_found(idx),
+            },
+            None => (400, error("item indices are non-negative integers")),
+        },
+        (Method::Get, ["search"]) => match search_param(query_string, views) {
+            Ok(sp) => (200, json!(tl.search(sp))),
+            Err(e) => e,
+        },
+        (Method::Get, ["count"]) => match search_param(query_string, views) {
+            Ok(sp) => (200, json!({"count": tl.count(sp)})),
+            Err(e) => e,
+        },
+        (Method::Put, ["views", name]) => match percent_decode(name).filter(|name| parser::is_word(name)) {
+            Some(name) => match search_param(query_string, views) {
+                Ok(sp) => match views.save(&name, sp) {
+                    Ok(()) => (200, json!({"name": name, "search": views.get(&name).map(SearchParams::to_string)})),
+                    Err(e) => (400, error(&e.0)),
+                },
+                Err(e) => e,
+            },
+            None => (400, error("view names are words, letters, digits and dashes")),
+        },
+        (Method::Get, ["views", name]) => match percent_decode(name) {
+            Some(name) => match views.get(&name) {
+                Some(sp) => (200, json!(tl.search(sp.clone()))),
+                None => (404, error(&format!("no search is saved as @{}", name))),
+            },
+            None => (400, error("view names are words, letters, digits and dashes")),
+        },
+        (Method::Get, ["stats"]) => (200, json!(tl.stats())),
+        (Method::Get, ["tags"]) => (200, json!(tl.tags(&normalise(&query_param(query_string, "prefix").unwrap_or_default())))),
+        (Method::Get, ["complete"]) => match query_param(query_string, "q") {
+            Some(q) => (200, json!(complete::suggestions(tl, &q, MAX_SUGGESTIONS))),
+            None => (400, error("missing text to complete, pass it as ?q=")),
+        },
+        (_, ["items"]) | (_, ["items", _]) | (_, ["items", _, "done"]) | (_, ["items", _, "tags"]) | (_, ["search"]) | (_, ["views", _]) | (_, ["count"]) | (_, ["stats"]) | (_, ["tags"]) | (_, ["complete"]) => {
+            (405, error("method not allowed"))
+        },
+        _ => (404, error("no such endpoint")),
+    }
+}
+
+//search terms passed as ?q=, with any saved searches they name expanded, or the response explaining why there aren't any
+fn search_param(query_string: &str, views: &Views) -> Result<SearchParams, (u16, Value)> {
+    match query_param(query_string, "q") {
+        Some(q) => match parser::search_terms(&q) {
+            Ok((_, sp)) => views.expand(&sp).map_err(|e| (400, error(&e.0))),
+            Err(e) => match parser::unsupported_sort_order(&e) {
+                Some(order) => Err((400, error(&format!("unsupported sort order \"{}\", items have no priority or due date", order)))),
+                None => Err((400, error(&format!("could not parse search terms \"{}\"", q)))),
+            },
+        },
+        None => Err((400, error("missing search terms, pass them as ?q="))),
+    }
+}
+
+fn add<T: TodoLister>(body: &str, tl: &mut T) -> (u16, Value) {
+    let new_item: NewItem = match serde_json::from_str(body) {
+        Ok(new_item) => new_item,
+        Err(e) => return (400, error(&format!("expected {{\"description\": [<word>...], \"tags\": [<tag>...]}}: {}", e))),
+    };
+    if new_item.description.is_empty() {
+        return (400, error("descriptions need at least one word"))
+    }
+    if let Some(w) = new_item.description.iter().chain(new_item.tags.iter()).find(|w| !parser::is_word(w)) {
+        return (400, error(&format!("\"{}\" is not a word, words are letters, digits and dashes", w)))
+    }
+    if tl.is_full() {
+        return (507, error("the list is full, no more items can be added"))
+    }
+    let description = new_item.description.iter().map(|w| Word::new(w)).collect();
+    let tags = new_item.tags.iter().map(|t| Tag::new(t)).collect();
+    (201, json!(tl.push(description, tags)))
+}
+
+fn edit_tags<T: TodoLister>(idx: Index, body: &str, tl: &mut T) -> (u16, Value) {
+    let changes: TagChanges = match serde_json::from_str(body) {
+        Ok(changes) => changes,
+        Err(e) => return (400, error(&format!("expected {{\"add\": [<tag>...], \"remove\": [<tag>...]}}: {}", e))),
+    };
+    if let Some(t) = changes.add.iter().chain(changes.remove.iter()).find(|t| !parser::is_word(t)) {
+        return (400, error(&format!("\"{}\" is not a word, words are letters, digits and dashes", t)))
+    }
+    let edit = TagEdit {
+        add: changes.add.iter().map(|t| Tag::new(t)).collect(),
+        remove: changes.remove.iter().map(|t| Tag::new(t)).collect(),
+    };
+    match tl.edit_tags(idx, &edit).and_then(|_| tl.get(idx)) {
+        Some(item) => (200, json!(item)),
+        None => not_found(idx),
+    }
+}
+
+fn parse_index(s: &str) -> Option<Index> {
+    s.parse().ok().map(Index::new)
+}
+
+fn not_found(idx: Index) -> (u16, Value) {
+    (404, error(&format!("no item with index {}", idx)))
+}
+
+fn error(reason: &str) -> Value {
+    json!({"error": reason})
+}
+
+//finds and decodes the value of a parameter in a url query string
+fn query_param(query_string: &str, name: &str) -> Option<String> {
+    query_string.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .and_then(|(_, value)| percent_decode(value))
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.bytes();
+    while let Some(b) = rest.next() {
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [rest.next()?, rest.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            },
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
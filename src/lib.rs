@@ -1,8 +1,22 @@
+pub mod cache;
+pub mod complete;
+pub mod concurrent;
+pub mod differential;
+pub mod generator;
+pub mod http;
+pub mod memory;
+pub mod observer;
 pub mod parser;
+pub mod planner;
+pub mod profile;
 pub mod query;
 pub mod runner;
+pub mod server;
+pub mod session;
+pub mod sharded;
 pub mod todo_list;
 pub mod trie;
+pub mod views;
 
 pub mod essd;
 pub mod fast_trie;
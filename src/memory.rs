@@ -0,0 +1,88 @@
+//Rough accounting of the memory each backend holds, so they can be weighed against each other by size as well as speed
+//Collection sizes are worked out from their capacities, following the layouts std uses today
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::mem::size_of;
+use std::ops::{Add, AddAssign};
+
+//all in bytes, except node_count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryUsage {
+    pub items: usize,
+    pub nodes: usize,
+    pub maps: usize,
+    pub id_sets: usize,
+    pub other: usize,
+    pub node_count: usize,
+}
+impl MemoryUsage {
+    pub fn total(&self) -> usize {
+        self.items + self.nodes + self.maps + self.id_sets + self.other
+    }
+}
+
+impl Add for MemoryUsage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        MemoryUsage {
+            items: self.items + other.items,
+            nodes: self.nodes + other.nodes,
+            maps: self.maps + other.maps,
+            id_sets: self.id_sets + other.id_sets,
+            other: self.other + other.other,
+            node_count: self.node_count + other.node_count,
+        }
+    }
+}
+
+impl AddAssign for MemoryUsage {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "items       {:>14} bytes", self.items)?;
+        writeln!(f, "trie nodes  {:>14} bytes ({} nodes)", self.nodes, self.node_count)?;
+        writeln!(f, "hash maps   {:>14} bytes", self.maps)?;
+        writeln!(f, "id sets     {:>14} bytes", self.id_sets)?;
+        writeln!(f, "other       {:>14} bytes", self.other)?;
+        write!(f, "total       {:>14} bytes", self.total())
+    }
+}
+
+pub(crate) fn vec_bytes<T>(v: &Vec<T>) -> usize {
+    v.capacity() * size_of::<T>()
+}
+
+//std's swiss tables allocate a power of two buckets, keeping one control byte per bucket plus a group's worth of padding
+//(a table holds at most 7/8ths of its buckets, or one less than its bucket count while it has fewer than 8)
+pub(crate) fn hash_table_bytes<T>(capacity: usize) -> usize {
+    const GROUP_WIDTH: usize = 16;
+    let buckets = match capacity {
+        0 => return 0,
+        1..=7 => capacity + 1,
+        _ => capacity / 7 * 8,
+    };
+    buckets * (size_of::<T>() + 1) + GROUP_WIDTH
+}
+
+pub(crate) fn hash_map_bytes<K, V, S>(m: &HashMap<K, V, S>) -> usize {
+    hash_table_bytes::<(K, V)>(m.capacity())
+}
+
+pub(crate) fn hash_set_bytes<T, S>(s: &HashSet<T, S>) -> usize {
+    hash_table_bytes::<T>(s.capacity())
+}
+
+//std's b-trees keep up to 11 entries in each node, here taken to be two thirds full on average,
+//with internal nodes (a small share of them) left out
+pub(crate) fn btree_map_bytes<K, V>(m: &BTreeMap<K, V>) -> usize {
+    const CAPACITY: usize = 11;
+    const HEADER: usize = 16; //parent pointer, position in the parent and length
+    let nodes = (m.len() * 3).div_ceil(CAPACITY * 2);
+    nodes * (CAPACITY * (size_of::<K>() + size_of::<V>()) + HEADER)
+}
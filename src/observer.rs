@@ -0,0 +1,192 @@
+//Change notification in front of any backend: subscribers hear about every push, done and tag edit as an event,
+//through a callback or a channel, optionally only for items matching a search
+//Events are sent as each change is made, before the change returns, so a bulk query sends one event per item it changes
+
+use crate::*;
+use crate::memory::MemoryUsage;
+use crate::todo_list::matches;
+use crate::views::Views;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Added (TodoItem),
+    //only sent the first time an item is done
+    Done (Index),
+    //the item as it is after its tags changed
+    Edited (TodoItem),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Callback = Box<dyn Fn(&Event) + Send + Sync>;
+
+enum Listener {
+    Callback (Callback),
+    Channel (Sender<Event>),
+}
+
+//filters are kept normalised and with any saved searches they name expanded, options play no part in them
+struct Subscription {
+    id: SubscriptionId,
+    filter: Option<SearchParams>,
+    listener: Listener,
+}
+impl Subscription {
+    //a filtered subscriber hears about an item if it matched the filter before the change or matches it after
+    fn wants(&self, items: &[&TodoItem]) -> bool {
+        match &self.filter {
+            None => true,
+            Some(sp) => items.iter().any(|item| matches(item, sp)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Subscribers {
+    next_id: u64,
+    subscriptions: Vec<Subscription>,
+}
+
+//subscribers are behind a mutex so that subscribing only needs a shared reference, even to a list shared between threads
+//(callbacks are called with it held, so they mustn't subscribe or unsubscribe on the list they're called from)
+pub struct ObservedTodoList<T: TodoLister> {
+    inner: T,
+    subscribers: Mutex<Subscribers>,
+}
+impl<T: TodoLister> ObservedTodoList<T> {
+    pub fn new(tl: T) -> Self {
+        ObservedTodoList {
+            inner: tl,
+            subscribers: Mutex::new(Subscribers::default()),
+        }
+    }
+
+    //calls back with every event for an item matching the filter, if there is one
+    //(any @names in the filter are expanded from the views as they are now, so saving over a view later
+    //doesn't change what an existing subscription hears about, subscribe again for that)
+    pub fn subscribe<F: Fn(&Event) + Send + Sync + 'static>(&self, filter: Option<SearchParams>, views: &Views, callback: F) -> Result<SubscriptionId, QueryError> {
+        self.add(filter, views, Listener::Callback(Box::new(callback)))
+    }
+
+    //sends every event for an item matching the filter, if there is one, down a channel
+    //(the subscription ends by itself once the receiver is dropped, and @names are expanded as they are for subscribe)
+    pub fn channel(&self, filter: Option<SearchParams>, views: &Views) -> Result<(SubscriptionId, Receiver<Event>), QueryError> {
+        let (sender, receiver) = mpsc::channel();
+        self.add(filter, views, Listener::Channel(sender)).map(|id| (id, receiver))
+    }
+
+    //whether there was such a subscription to end
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscribers = self.lock();
+        let before = subscribers.subscriptions.len();
+        subscribers.subscriptions.retain(|s| s.id != id);
+        subscribers.subscriptions.len() != before
+    }
+
+    pub fn subscriptions(&self) -> usize {
+        self.lock().subscriptions.len()
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn add(&self, filter: Option<SearchParams>, views: &Views, listener: Listener) -> Result<SubscriptionId, QueryError> {
+        let filter = filter.map(|sp| views.expand(&sp).map(|sp| sp.normalised())).transpose()?;
+        let mut subscribers = self.lock();
+        let id = SubscriptionId(subscribers.next_id);
+        subscribers.next_id += 1;
+        subscribers.subscriptions.push(Subscription{id, filter, listener});
+        Ok(id)
+    }
+
+    //sends an event to every subscriber wanting to hear about any of the items, dropping channels nobody is listening to
+    fn send(&self, event: Event, items: &[&TodoItem]) {
+        self.lock().subscriptions.retain(|s| {
+            if !s.wants(items) {
+                return true
+            }
+            match &s.listener {
+                Listener::Callback(callback) => {
+                    callback(&event);
+                    true
+                },
+                Listener::Channel(sender) => sender.send(event.clone()).is_ok(),
+            }
+        });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Subscribers> {
+        self.subscribers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+impl<T: TodoLister + Default> Default for ObservedTodoList<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+impl<T: TodoLister + fmt::Debug> fmt::Debug for ObservedTodoList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ObservedTodoList").field("inner", &self.inner).field("subscriptions", &self.subscriptions()).finish()
+    }
+}
+impl<T: TodoLister> TodoLister for ObservedTodoList<T> {
+    fn push(&mut self, description: Vec<Word>, tags: Vec<Tag>) -> TodoItem {
+        let item = self.inner.push(description, tags);
+        self.send(Event::Added(item.clone()), &[&item]);
+        item
+    }
+    //filters are matched against the item while it's still open, as done items match nothing
+    fn done_with_index(&mut self, idx: Index) -> Option<Index> {
+        let open = self.inner.get(idx).filter(|item| !item.done).cloned();
+        let done = self.inner.done_with_index(idx)?;
+        if let Some(item) = open {
+            self.send(Event::Done(idx), &[&item]);
+        }
+        Some(done)
+    }
+    fn search(&self, sp: SearchParams) -> Vec<&TodoItem> {
+        self.inner.search(sp)
+    }
+    fn get(&self, idx: Index) -> Option<&TodoItem> {
+        self.inner.get(idx)
+    }
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+    fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+    fn count(&self, sp: SearchParams) -> usize {
+        self.inner.count(sp)
+    }
+    fn stats(&self) -> ListStats {
+        self.inner.stats()
+    }
+    fn tags(&self, prefix: &str) -> BTreeMap<String, usize> {
+        self.inner.tags(prefix)
+    }
+    fn edit_tags(&mut self, idx: Index, edit: &TagEdit) -> Option<bool> {
+        let before = self.inner.get(idx)?.clone();
+        let changed = self.inner.edit_tags(idx, edit)?;
+        if changed {
+            if let Some(after) = self.inner.get(idx) {
+                self.send(Event::Edited(after.clone()), &[&before, after]);
+            }
+        }
+        Some(changed)
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        self.inner.memory_usage()
+    }
+}
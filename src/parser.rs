@@ -6,20 +6,46 @@ use nom::{
     branch::alt,
     character::complete::{one_of, digit1},
     multi::{separated_nonempty_list, many0},
-    sequence::{pair, preceded, delimited},
-    combinator::opt,
+    sequence::{pair, preceded, delimited, tuple},
+    combinator::{opt, all_consuming, cut, map, map_res},
+    error::ErrorKind,
 };
+use unicode_normalization::char::is_combining_mark;
 
 //Specification parsers
 
 /*Assumptions:
 -Whitespace between tokens in the specification grammar represent a single space or tab character (as parsed by the 'ws' function below)
+-<word>s consist of any mix of letters, digits, combining marks and dashes, in any script and case (matching is done on their normalised form)
 -Whitespace is not required following a <description> if the add query's list of <tags> is empty
 -<description>s consist of at least one <word>
+-Searches can mix limit:<n>, offset:<n> and sort:<index|-index|score> in among their terms, each at most once,
+ but need at least one term (a term starting with an option's name and colon is always that option, and a bad value fails the whole query)
+-Every query but add has to take up the whole line, so nothing after one goes ignored
+-Bulk queries are "done where <search>" and "tag <add|remove> <tags> where <search>", with at least one tag
+-Tag edits are "tag <index>" followed by one or more +#<tag> or -#<tag>, in any order
+-Saved searches are named with a <word>, and searches can name them as @<word> among their terms (which counts as a term)
 */
 
 pub fn query(input : &str) -> IResult<&str, Query> {
-    alt((add, done, search))(input)
+    alt((add, done, done_where, search, count, stats, tags_query, tag_where, edit_tags, save, view))(input)
+}
+
+//whole-input parsers for the pieces of a query, for front ends which are handed them separately rather than as a line of text
+pub fn search_terms(input : &str) -> IResult<&str, SearchParams> {
+    all_consuming(search_query)(input)
+}
+
+pub fn is_word(input : &str) -> bool {
+    all_consuming(prim_word)(input).is_ok()
+}
+
+//the order a query failed on for asking to sort by something items don't have, to explain the failure with
+pub fn unsupported_sort_order<'a>(e : &nom::Err<(&'a str, ErrorKind)>) -> Option<&'a str> {
+    match e {
+        nom::Err::Failure((rest, ErrorKind::Verify)) => prim_word(rest).ok().map(|(_, order)| order),
+        _ => None,
+    }
 }
 
 fn add(input : &str) -> IResult<&str, Query> {
@@ -33,25 +59,92 @@ fn add(input : &str) -> IResult<&str, Query> {
 }
 
 fn done(input : &str) -> IResult<&str, Query> {
-    match preceded(
+    match all_consuming(preceded(
         pair(tag("done"), ws),
         index
-    )(input) {
+    ))(input) {
         Err(e) => Err(e),
         Ok((rest, i)) => Ok((rest, Query::Done(i))),
     }
 }
 
+fn done_where(input : &str) -> IResult<&str, Query> {
+    all_consuming(preceded(tuple((tag("done"), ws, tag("where"), ws)), search_query))(input).map(|(rest, p)| (rest, Query::DoneWhere(p)))
+}
+
+fn tag_where(input : &str) -> IResult<&str, Query> {
+    all_consuming(preceded(
+        pair(tag("tag"), ws),
+        tuple((
+            tag_change,
+            preceded(ws, separated_nonempty_list(tag(" "), todo_tag)),
+            preceded(tuple((ws, tag("where"), ws)), search_query),
+        ))
+    ))(input).map(|(rest, (change, ts, p))| (rest, Query::TagWhere(change, ts, p)))
+}
+
+fn edit_tags(input : &str) -> IResult<&str, Query> {
+    map(
+        all_consuming(preceded(pair(tag("tag"), ws), pair(index, preceded(ws, separated_nonempty_list(tag(" "), tag_edit_item))))),
+        |(idx, items)| {
+            let mut edit = TagEdit::default();
+            for (adding, t) in items {
+                if adding { edit.add.push(t) } else { edit.remove.push(t) }
+            }
+            Query::EditTags(idx, edit)
+        }
+    )(input)
+}
+
+//true for a tag to add, false for one to remove
+fn tag_edit_item(input : &str) -> IResult<&str, (bool, Tag)> {
+    alt((
+        map(preceded(tag("+"), todo_tag), |t| (true, t)),
+        map(preceded(tag("-"), todo_tag), |t| (false, t)),
+    ))(input)
+}
+
+fn save(input : &str) -> IResult<&str, Query> {
+    all_consuming(preceded(pair(tag("save"), ws), pair(prim_word, preceded(ws, search_query))))(input)
+        .map(|(rest, (name, p))| (rest, Query::Save(name.to_owned(), p)))
+}
+
+fn view(input : &str) -> IResult<&str, Query> {
+    all_consuming(preceded(pair(tag("view"), ws), prim_word))(input).map(|(rest, name)| (rest, Query::View(name.to_owned())))
+}
+
+fn tag_change(input : &str) -> IResult<&str, TagChange> {
+    alt((
+        map(tag("add"), |_| TagChange::Add),
+        map(tag("remove"), |_| TagChange::Remove),
+    ))(input)
+}
+
 fn search(input : &str) -> IResult<&str, Query> {
-    match preceded(
+    match all_consuming(preceded(
         pair(tag("search"), ws),
         search_query
-    )(input) {
+    ))(input) {
         Err(e) => Err(e),
         Ok((rest, p)) => Ok((rest, Query::Search(p))),
     }
 }
 
+fn count(input : &str) -> IResult<&str, Query> {
+    all_consuming(preceded(pair(tag("count"), ws), search_query))(input).map(|(rest, p)| (rest, Query::Count(p)))
+}
+
+//nothing may follow, so that stats can't be mistaken for the start of something else
+fn stats(input : &str) -> IResult<&str, Query> {
+    all_consuming(tag("stats"))(input).map(|(rest, _)| (rest, Query::Stats))
+}
+
+//the prefix can be written with or without the # before it
+fn tags_query(input : &str) -> IResult<&str, Query> {
+    all_consuming(preceded(tag("tags"), opt(preceded(pair(ws, opt(tag("#"))), prim_word))))(input)
+        .map(|(rest, prefix)| (rest, Query::Tags(prefix.map(String::from))))
+}
+
 fn description(input : &str) -> IResult<&str, Vec<Word>> {
     separated_nonempty_list(tag(" "), word)(input)
 }
@@ -78,12 +171,65 @@ fn todo_tag(input : &str) -> IResult<&str, Tag> {
     preceded(tag("#"), prim_word)(input).map(|(rest, w)| (rest, Tag::new(w)))
 }
 
+//indices too big for a u64 are parse errors rather than panics
 fn index(input : &str) -> IResult<&str, Index> {
-    digit1(input).map(|(rest, v)| (rest, Index::new(v.parse().unwrap())))
+    map_res(digit1, str::parse)(input).map(|(rest, v)| (rest, Index::new(v)))
 }
 
 fn search_query(input : &str) -> IResult<&str, SearchParams> {
-    separated_nonempty_list(tag(" "), search_word_or_tag)(input).map(|(rest, p)| (rest, SearchParams{params: p}))
+    map_res(separated_nonempty_list(tag(" "), search_item), search_params)(input)
+}
+
+enum SearchItem {
+    Term (SearchWordOrTag),
+    Limit (usize),
+    Offset (usize),
+    Sort (SortOrder),
+    View (String),
+}
+
+fn search_item(input : &str) -> IResult<&str, SearchItem> {
+    alt((
+        map(preceded(tag("limit:"), cut(number)), SearchItem::Limit),
+        map(preceded(tag("offset:"), cut(number)), SearchItem::Offset),
+        map(preceded(tag("sort:"), cut(sort_order)), SearchItem::Sort),
+        map(preceded(tag("@"), prim_word), |name| SearchItem::View(name.to_owned())),
+        map(search_word_or_tag, SearchItem::Term),
+    ))(input)
+}
+
+fn search_params(items : Vec<SearchItem>) -> Result<SearchParams, &'static str> {
+    let mut sp = SearchParams::new(Vec::new());
+    for item in items {
+        match item {
+            SearchItem::Term(t) => sp.params.push(t),
+            SearchItem::View(name) => sp.views.push(name),
+            SearchItem::Limit(n) if sp.options.limit.is_none() => sp.options.limit = Some(n),
+            SearchItem::Offset(n) if sp.options.offset.is_none() => sp.options.offset = Some(n),
+            SearchItem::Sort(o) if sp.options.sort.is_none() => sp.options.sort = Some(o),
+            _ => return Err("search options can only be given once"),
+        }
+    }
+    if sp.params.is_empty() && sp.views.is_empty() {
+        return Err("searches need at least one term")
+    }
+    Ok(sp)
+}
+
+fn number(input : &str) -> IResult<&str, usize> {
+    map_res(digit1, str::parse)(input)
+}
+
+//there's no sorting by priority or due date, since items have neither, so asking for them fails in a way unsupported_sort_order recognises
+fn sort_order(input : &str) -> IResult<&str, SortOrder> {
+    let (rest, order) = prim_word(input)?;
+    match order {
+        "index" => Ok((rest, SortOrder::Index)),
+        "-index" => Ok((rest, SortOrder::IndexDescending)),
+        "score" => Ok((rest, SortOrder::Score)),
+        "priority" | "due" => Err(nom::Err::Failure((input, ErrorKind::Verify))),
+        _ => Err(nom::Err::Error((input, ErrorKind::MapRes))),
+    }
 }
 
 //Helper parsers and functions
@@ -91,7 +237,7 @@ fn search_word_or_tag(input : &str) -> IResult<&str, SearchWordOrTag> {
     match alt((pair(tag("#"), prim_word), pair(tag(""), prim_word)))(input) {
         Err(e) => Err(e),
         Ok((rest, (hash, wot))) => {
-            if hash.starts_with("#") {
+            if hash.starts_with('#') {
                 Ok( (rest, SearchWordOrTag::RawTag(wot.to_string())) )
             } else {
                 Ok( (rest, SearchWordOrTag::RawWord(wot.to_string())) )
@@ -100,10 +246,10 @@ fn search_word_or_tag(input : &str) -> IResult<&str, SearchWordOrTag> {
     }
 }
 fn prim_word(input : &str) -> IResult<&str, &str> {
-    take_while1(is_lowecase_or_dash)(input)
+    take_while1(is_word_char)(input)
 }
-fn is_lowecase_or_dash(c : char) -> bool {
-    c.is_ascii_lowercase() || c == '-'
+fn is_word_char(c : char) -> bool {
+    c.is_alphanumeric() || is_combining_mark(c) || c == '-'
 }
 fn ws(input : &str) -> IResult<&str, char> { 
     one_of(" \t")(input)
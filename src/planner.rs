@@ -0,0 +1,23 @@
+//Orders a search's terms so the ones expected to match fewest items run first,
+//which leaves later terms (whether words or tags) searching through a smaller filter
+//Estimates only decide the order, every term is still searched and intersected, so a bad estimate costs time but never results
+
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannedTerm<'a> {
+    pub term: &'a str,
+    pub is_tag: bool,
+    pub estimate: u64,
+}
+
+//expects normalised search params, since estimates are read from tries keyed on normalised words and tags
+//(ties go to the longer term, which can match fewer words)
+pub fn plan<'a, T: Trie>(sp: &'a SearchParams, words: &T, tags: &T) -> Vec<PlannedTerm<'a>> {
+    let mut terms: Vec<PlannedTerm> = sp.params.iter().map(|param| match param {
+        SearchWordOrTag::RawWord(w) => PlannedTerm{term: w, is_tag: false, estimate: words.estimate(w)},
+        SearchWordOrTag::RawTag(t) => PlannedTerm{term: t, is_tag: true, estimate: tags.estimate(t)},
+    }).collect();
+    terms.sort_by_key(|t| (t.estimate, std::cmp::Reverse(t.term.chars().count())));
+    terms
+}
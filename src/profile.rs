@@ -0,0 +1,157 @@
+//Per-query timing and work counts, with a log of the queries which took too long
+//Backends add up the work they do into counters kept per thread, which runner::run_line_profiled reads after each query
+
+use crate::*;
+
+use std::cell::Cell;
+use std::fmt;
+use std::io::Write;
+use std::time::Duration;
+
+thread_local! {
+    static COUNTERS: Cell<WorkCounters> = Cell::new(WorkCounters::default());
+}
+
+//nodes_visited counts trie nodes taken off the to-visit stack (or recursed into),
+//candidates counts items the scanning backends test against search terms, or ids the tries hand back to be intersected,
+//gathered counts matches held all together: results collected to be sorted, paged or counted, and ids kept to intersect later terms with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WorkCounters {
+    pub nodes_visited: u64,
+    pub candidates: u64,
+    pub gathered: u64,
+}
+
+pub(crate) fn count_nodes_visited(n: u64) {
+    COUNTERS.with(|c| c.set(WorkCounters{nodes_visited: c.get().nodes_visited + n, ..c.get()}));
+}
+
+pub(crate) fn count_candidates(n: usize) {
+    COUNTERS.with(|c| c.set(WorkCounters{candidates: c.get().candidates + n as u64, ..c.get()}));
+}
+
+pub(crate) fn count_gathered(n: usize) {
+    COUNTERS.with(|c| c.set(WorkCounters{gathered: c.get().gathered + n as u64, ..c.get()}));
+}
+
+//the work counted on this thread since the counters were last taken, resetting them
+pub fn take_counters() -> WorkCounters {
+    COUNTERS.with(|c| c.replace(WorkCounters::default()))
+}
+
+//queries which failed to parse or run are kept apart as errors,
+//and counts, stats, tag listings and views are looked up much as searches are, so are recorded along with them
+//(bulk queries are recorded with the change they make, including the search which picks the items, and saving a search is an edit)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    Add,
+    Done,
+    Edit,
+    Search,
+    Error,
+}
+impl QueryKind {
+    pub fn of(q: &Query) -> Self {
+        match q {
+            Query::Add(..) => QueryKind::Add,
+            Query::Done(_) | Query::DoneWhere(_) => QueryKind::Done,
+            Query::TagWhere(..) | Query::EditTags(..) | Query::Save(..) => QueryKind::Edit,
+            Query::Search(_) | Query::Count(_) | Query::Stats | Query::Tags(_) | Query::View(_) => QueryKind::Search,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KindStats {
+    pub count: u64,
+    pub total_time: Duration,
+    pub max_time: Duration,
+    pub nodes_visited: u64,
+    pub candidates: u64,
+    pub gathered: u64,
+}
+impl KindStats {
+    fn record(&mut self, elapsed: Duration, work: WorkCounters) {
+        self.count += 1;
+        self.total_time += elapsed;
+        self.max_time = self.max_time.max(elapsed);
+        self.nodes_visited += work.nodes_visited;
+        self.candidates += work.candidates;
+        self.gathered += work.gathered;
+    }
+}
+
+//totals for each kind of query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProfileStats {
+    pub adds: KindStats,
+    pub dones: KindStats,
+    pub edits: KindStats,
+    pub searches: KindStats,
+    pub errors: KindStats,
+    pub slow_queries: u64,
+}
+
+impl fmt::Display for ProfileStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:<9}{:>10}{:>14}{:>14}{:>14}{:>16}{:>14}{:>14}", "query", "count", "total ms", "mean us", "max us", "nodes visited", "candidates", "gathered")?;
+        for (name, s) in &[("add", self.adds), ("done", self.dones), ("edit", self.edits), ("search", self.searches), ("error", self.errors)] {
+            let mean = if s.count == 0 { 0 } else { s.total_time.as_micros() / u128::from(s.count) };
+            write!(f, "\n{:<9}{:>10}{:>14}{:>14}{:>14}{:>16}{:>14}{:>14}", name, s.count, s.total_time.as_millis(), mean,
+                s.max_time.as_micros(), s.nodes_visited, s.candidates, s.gathered)?;
+        }
+        write!(f, "\n{} slow queries", self.slow_queries)
+    }
+}
+
+//records every query it's handed, logging those which take at least slow_threshold
+pub struct Profiler {
+    slow_threshold: Duration,
+    slow_log: Option<Box<dyn Write + Send>>,
+    stats: ProfileStats,
+}
+impl Profiler {
+    pub fn new(slow_threshold: Duration) -> Self {
+        Profiler {
+            slow_threshold,
+            slow_log: None,
+            stats: ProfileStats::default(),
+        }
+    }
+
+    //slow queries are written one per line as: <micros>us nodes=<n> candidates=<n> gathered=<n> <query line>
+    pub fn with_slow_log(mut self, log: Box<dyn Write + Send>) -> Self {
+        self.slow_log = Some(log);
+        self
+    }
+
+    pub fn stats(&self) -> &ProfileStats {
+        &self.stats
+    }
+
+    pub fn record(&mut self, line: &str, kind: QueryKind, elapsed: Duration, work: WorkCounters) {
+        let stats = match kind {
+            QueryKind::Add => &mut self.stats.adds,
+            QueryKind::Done => &mut self.stats.dones,
+            QueryKind::Edit => &mut self.stats.edits,
+            QueryKind::Search => &mut self.stats.searches,
+            QueryKind::Error => &mut self.stats.errors,
+        };
+        stats.record(elapsed, work);
+        if elapsed >= self.slow_threshold {
+            self.stats.slow_queries += 1;
+            if let Some(log) = &mut self.slow_log {
+                if let Err(e) = writeln!(log, "{}us nodes={} candidates={} gathered={} {}", elapsed.as_micros(), work.nodes_visited, work.candidates,
+                    work.gathered, line) {
+                    eprintln!("Could not write to slow query log: {}", e);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Profiler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Profiler").field("slow_threshold", &self.slow_threshold).field("stats", &self.stats).finish()
+    }
+}
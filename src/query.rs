@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
 use std::fmt;
 
+use serde::Serialize;
+
 use crate::*;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -7,11 +10,185 @@ pub enum Query {
     Add (Vec<Word>, Vec<Tag>),
     Done (Index),
     Search (SearchParams),
+    Count (SearchParams),
+    Stats,
+    Tags (Option<String>),
+    //marks every open item a search finds done
+    DoneWhere (SearchParams),
+    //adds or removes tags on every open item a search finds
+    TagWhere (TagChange, Vec<Tag>, SearchParams),
+    EditTags (Index, TagEdit),
+    //saves a search under a name, for view and for @<name> in other searches
+    Save (String, SearchParams),
+    View (String),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+//writes queries back out in the syntax the parser reads, so that parsing the text gives back an equal query
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Query::Add(description, tags) => {
+                let words: Vec<String> = description.iter().map(Word::to_string).collect();
+                write!(f, "add \"{}\"", words.join(" "))?;
+                for tag in tags {
+                    write!(f, " {}", tag)?;
+                }
+                Ok(())
+            },
+            Query::Done(idx) => write!(f, "done {}", idx),
+            Query::Search(params) => write!(f, "search {}", params),
+            Query::Count(params) => write!(f, "count {}", params),
+            Query::Stats => write!(f, "stats"),
+            Query::Tags(None) => write!(f, "tags"),
+            Query::Tags(Some(prefix)) => write!(f, "tags {}", prefix),
+            Query::DoneWhere(params) => write!(f, "done where {}", params),
+            Query::TagWhere(change, tags, params) => {
+                write!(f, "tag {}", change)?;
+                for tag in tags {
+                    write!(f, " {}", tag)?;
+                }
+                write!(f, " where {}", params)
+            },
+            Query::Save(name, params) => write!(f, "save {} {}", name, params),
+            Query::View(name) => write!(f, "view {}", name),
+            Query::EditTags(idx, edit) => {
+                write!(f, "tag {}", idx)?;
+                for tag in &edit.add {
+                    write!(f, " +{}", tag)?;
+                }
+                for tag in &edit.remove {
+                    write!(f, " -{}", tag)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagChange {
+    Add,
+    Remove,
+}
+impl TagChange {
+    pub fn edit(self, tags: Vec<Tag>) -> TagEdit {
+        match self {
+            TagChange::Add => TagEdit{add: tags, remove: Vec::new()},
+            TagChange::Remove => TagEdit{add: Vec::new(), remove: tags},
+        }
+    }
+}
+
+impl fmt::Display for TagChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TagChange::Add => write!(f, "add"),
+            TagChange::Remove => write!(f, "remove"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SearchParams {
     pub params : Vec<todo_list::SearchWordOrTag>,
+    pub options : SearchOptions,
+    //names of saved searches whose terms are part of this one, which backends never see, as views::Views::expand swaps them for the terms
+    pub views : Vec<String>,
+}
+impl SearchParams {
+    pub fn new(params: Vec<SearchWordOrTag>) -> Self {
+        SearchParams {
+            params,
+            options: SearchOptions::default(),
+            views: Vec::new(),
+        }
+    }
+
+    //search terms with the same normalisation applied as to the keys of Words and Tags
+    pub fn normalised(&self) -> SearchParams {
+        let params = self.params.iter().map(|param| match param {
+            SearchWordOrTag::RawWord(w) => SearchWordOrTag::RawWord(normalise(w)),
+            SearchWordOrTag::RawTag(t) => SearchWordOrTag::RawTag(normalise(t)),
+        }).collect();
+        SearchParams{params, options: self.options, views: self.views.iter().map(|v| normalise(v)).collect()}
+    }
+
+    //the same terms, finding every match in whatever order the backend finds them
+    pub fn without_options(&self) -> SearchParams {
+        SearchParams{views: self.views.clone(), ..SearchParams::new(self.params.clone())}
+    }
+}
+
+impl fmt::Display for SearchParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms: Vec<String> = self.params.iter().map(SearchWordOrTag::to_string).chain(self.views.iter().map(|v| format!("@{}", v))).collect();
+        write!(f, "{}{}", terms.join(" "), self.options)
+    }
+}
+
+//items have no priority or due date, so index and score are the only orders there are
+//(asking for either is a parse error which says so, see parser::unsupported_sort_order)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    Index,
+    IndexDescending,
+    //best matches first (see todo_list::score), then by index
+    Score,
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SortOrder::Index => write!(f, "index"),
+            SortOrder::IndexDescending => write!(f, "-index"),
+            SortOrder::Score => write!(f, "score"),
+        }
+    }
+}
+
+//with none of these a search finds every match, in whatever order the backend finds them,
+//with any of them results are sorted (by index unless asked otherwise) so that pages are the same from every backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SearchOptions {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub sort: Option<SortOrder>,
+}
+impl SearchOptions {
+    pub fn is_empty(&self) -> bool {
+        *self == SearchOptions::default()
+    }
+
+    //the order results are put in, if they are sorted at all
+    pub fn order(&self) -> Option<SortOrder> {
+        if self.is_empty() { None } else { Some(self.sort.unwrap_or(SortOrder::Index)) }
+    }
+
+    //how many sorted results are needed to fill the page, if not all of them
+    pub fn needed(&self) -> Option<usize> {
+        self.limit.map(|limit| limit.saturating_add(self.offset.unwrap_or(0)))
+    }
+
+    //how many results the page holds, out of this many matches
+    pub fn page_len(&self, matches: usize) -> usize {
+        matches.saturating_sub(self.offset.unwrap_or(0)).min(self.limit.unwrap_or(usize::MAX))
+    }
+}
+
+//written as they follow search terms, including the space before them
+impl fmt::Display for SearchOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(limit) = self.limit {
+            write!(f, " limit:{}", limit)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " offset:{}", offset)?;
+        }
+        if let Some(sort) = self.sort {
+            write!(f, " sort:{}", sort)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -19,6 +196,15 @@ pub enum QueryResult {
     Added (TodoItem),
     Done,
     Found (Vec<todo_list::TodoItem>),
+    Count (usize),
+    Stats (ListStats),
+    //open items carrying each tag
+    Tags (BTreeMap<String, usize>),
+    //how many items a bulk query changed
+    Affected (usize),
+    //the item as it is after its tags were edited
+    Edited (TodoItem),
+    Saved (String),
 }
 
 impl fmt::Display for QueryResult {
@@ -33,8 +219,49 @@ impl fmt::Display for QueryResult {
                     buff.push(format!("{}", i));
                 }
                 write!(f, "{}", buff.join("\n"))
-            }
+            },
+            QueryResult::Count(n) => write!(f, "{}", n),
+            QueryResult::Stats(stats) => write!(f, "{}", stats),
+            QueryResult::Tags(tags) => {
+                write!(f, "{} tag(s) found", tags.len())?;
+                for (tag, open) in tags {
+                    write!(f, "\n#{} {} open", tag, open)?;
+                }
+                Ok(())
+            },
+            QueryResult::Affected(n) => write!(f, "{} item(s) affected", n),
+            QueryResult::Edited(item) => write!(f, "{}", item),
+            QueryResult::Saved(name) => write!(f, "saved @{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Counts {
+    pub open: usize,
+    pub done: usize,
+}
+
+impl fmt::Display for Counts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} open, {} done", self.open, self.done)
+    }
+}
+
+//counts of every item, and of the items with each (normalised) tag
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct ListStats {
+    pub items: Counts,
+    pub tags: BTreeMap<String, Counts>,
+}
+
+impl fmt::Display for ListStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.items)?;
+        for (tag, counts) in &self.tags {
+            write!(f, "\n#{} {}", tag, counts)?;
         }
+        Ok(())
     }
 }
 
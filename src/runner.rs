@@ -1,25 +1,75 @@
 use crate::*;
+use crate::profile::{self, Profiler, QueryKind};
+use crate::session::SearchSession;
+use crate::views::Views;
 
-pub fn run_line<T: TodoLister>(line: &str, tl: &mut T) -> Option<QueryResult> {
-    match parser::query(line) {
-        Ok((_, q)) => match run_query(q, tl) {
-            Ok(r) => Some(r),
-            Err(e) => { 
-                eprintln!("Error: {}", e);
-                None
-            },
-        }
+use std::time::Instant;
+
+//searches are saved in the views kept next to the list, which expand the @names in a search before the list sees it
+pub fn run_line<T: TodoLister>(line: &str, tl: &mut T, views: &mut Views) -> Option<QueryResult> {
+    match try_run_line(line, tl, views) {
+        Ok(r) => Some(r),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            None
+        },
+    }
+}
+
+//as run_line, but hands errors back to the caller instead of reporting them on stderr
+pub fn try_run_line<T: TodoLister>(line: &str, tl: &mut T, views: &mut Views) -> Result<QueryResult, QueryError> {
+    parse_line(line).and_then(|q| run_query(q, tl, views))
+}
+
+//as try_run_line, with searches refining the session's previous results where they can
+pub fn try_run_line_in_session<T: TodoLister>(line: &str, tl: &mut T, views: &mut Views, session: &mut SearchSession) -> Result<QueryResult, QueryError> {
+    match parse_line(line)? {
+        Query::Search(params) => run_search_in_session(params, tl, views, session),
+        q if edits_tags(&q) => {
+            session.reset();
+            run_query(q, tl, views)
+        },
+        q => run_query(q, tl, views),
+    }
+}
+
+//as run_line, also timing the query and counting the work it took, for the profiler to record
+pub fn run_line_profiled<T: TodoLister>(line: &str, tl: &mut T, views: &mut Views, profiler: &mut Profiler) -> Option<QueryResult> {
+    profile::take_counters(); //drop anything counted outside of a query
+    let start = Instant::now();
+    let mut kind = QueryKind::Error;
+    let result = parse_line(line).and_then(|q| {
+        kind = QueryKind::of(&q);
+        run_query(q, tl, views)
+    });
+    let elapsed = start.elapsed();
+    let kind = if result.is_ok() { kind } else { QueryKind::Error };
+    profiler.record(line, kind, elapsed, profile::take_counters());
+    match result {
+        Ok(r) => Some(r),
         Err(e) => {
-            eprintln!("Error: {}", e); 
-            eprintln!("Attempted to parse: \"{}\"", line);
+            eprintln!("Error: {}", e);
             None
-        }, 
+        },
+    }
+}
+
+pub fn parse_line(line: &str) -> Result<Query, QueryError> {
+    match parser::query(line) {
+        Ok((_, q)) => Ok(q),
+        Err(e) => match parser::unsupported_sort_order(&e) {
+            Some(order) => Err(QueryError(format!("unsupported sort order \"{}\", items have no priority or due date (attempted to parse \"{}\")", order, line))),
+            None => Err(QueryError(format!("{} (attempted to parse \"{}\")", e, line))),
+        },
     }
 }
 
-fn run_query<T: TodoLister>(q: Query, tl: &mut T) -> Result<QueryResult, QueryError> {
+pub fn run_query<T: TodoLister>(q: Query, tl: &mut T, views: &mut Views) -> Result<QueryResult, QueryError> {
     match q {
         Query::Add(desc, tags) => {
+            if tl.is_full() {
+                return Err(QueryError(String::from("Attempted to add an item to a full list")))
+            }
             let item = tl.push(desc, tags);
             Ok(query::QueryResult::Added(item))
         },
@@ -29,10 +79,67 @@ fn run_query<T: TodoLister>(q: Query, tl: &mut T) -> Result<QueryResult, QueryEr
                 None => Err(QueryError(String::from("Attempted to mark non-existent item as Done"))),
             }
         },
-        Query::Search(params) => {
-            let results = tl.search(params);
-            let results = results.into_iter().map(|r| r.clone()).collect();
-            Ok(query::QueryResult::Found(results))
+        //the search and the changes it drives happen under the one mutable borrow, so nothing can change the list in between
+        Query::DoneWhere(params) => {
+            let found = found_indices(views.expand(&params)?, tl);
+            Ok(query::QueryResult::Affected(found.into_iter().filter(|idx| tl.done_with_index(*idx).is_some()).count()))
+        },
+        Query::TagWhere(change, tags, params) => {
+            let edit = change.edit(tags);
+            let found = found_indices(views.expand(&params)?, tl);
+            Ok(query::QueryResult::Affected(found.into_iter().filter(|idx| tl.edit_tags(*idx, &edit) == Some(true)).count()))
+        },
+        Query::EditTags(idx, edit) => {
+            match tl.edit_tags(idx, &edit).and_then(|_| tl.get(idx)) {
+                Some(item) => Ok(query::QueryResult::Edited(item.clone())),
+                None => Err(QueryError(String::from("Attempted to edit the tags of non-existent item"))),
+            }
+        },
+        Query::Save(name, params) => {
+            views.save(&name, params)?;
+            Ok(query::QueryResult::Saved(name))
+        },
+        q => run_read_only(q, tl, views),
+    }
+}
+
+//every item a search finds, or the page of them it asks for
+fn found_indices<T: TodoLister>(params: SearchParams, tl: &T) -> Vec<Index> {
+    tl.search(params).iter().map(|item| item.index).collect()
+}
+
+pub fn changes_list(q: &Query) -> bool {
+    matches!(q, Query::Add(..) | Query::Done(_) | Query::DoneWhere(_) | Query::TagWhere(..) | Query::EditTags(..) | Query::Save(..))
+}
+
+//queries which can change items' tags, after which sessions can't narrow down what they found before
+pub fn edits_tags(q: &Query) -> bool {
+    matches!(q, Query::TagWhere(..) | Query::EditTags(..))
+}
+
+//queries which don't change the list can be run through a shared reference
+pub fn run_read_only<T: TodoLister>(q: Query, tl: &T, views: &Views) -> Result<QueryResult, QueryError> {
+    match q {
+        Query::Search(params) => Ok(run_search(views.expand(&params)?, tl)),
+        Query::Count(params) => Ok(query::QueryResult::Count(tl.count(views.expand(&params)?))),
+        Query::View(name) => match views.get(&name) {
+            Some(params) => Ok(run_search(params.clone(), tl)),
+            None => Err(QueryError(format!("no search is saved as @{}", name))),
         },
+        Query::Stats => Ok(query::QueryResult::Stats(tl.stats())),
+        Query::Tags(prefix) => Ok(query::QueryResult::Tags(tl.tags(&normalise(prefix.as_deref().unwrap_or(""))))),
+        q => Err(QueryError(format!("\"{}\" changes the list, so can't be run on a shared one", q))),
     }
 }
+
+//searches naming saved searches have to be expanded first, see views::Views::expand
+pub fn run_search<T: TodoLister>(params: SearchParams, tl: &T) -> QueryResult {
+    let results = tl.search(params);
+    let results = results.into_iter().cloned().collect();
+    query::QueryResult::Found(results)
+}
+
+pub fn run_search_in_session<T: TodoLister>(params: SearchParams, tl: &T, views: &Views, session: &mut SearchSession) -> Result<QueryResult, QueryError> {
+    let results = session.search(tl, views.expand(&params)?);
+    Ok(query::QueryResult::Found(results.into_iter().cloned().collect()))
+}
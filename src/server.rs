@@ -0,0 +1,64 @@
+//Shares one todo list between any number of local clients, over a unix domain socket or a localhost tcp port
+
+//Protocol: clients send one query per line, in the same grammar as standard input.
+//Each response is the result as the application would print it (or a line starting "error: "),
+//followed by a single empty line, which can never occur inside a result, to mark where it ends.
+//Adds and dones are applied one at a time, in the order the server receives them, whichever client sent them,
+//while searches from different clients can run in parallel with each other (see concurrent.rs).
+
+use crate::*;
+use crate::concurrent::ConcurrentTodoList;
+use crate::session::SearchSession;
+
+use std::io::{self, prelude::*};
+use std::net::{TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::Arc;
+use std::thread;
+
+pub fn serve_tcp<T: TodoLister + Send + Sync + 'static>(listener: TcpListener, tl: T) -> io::Result<()> {
+    let tl = Arc::new(ConcurrentTodoList::new(tl));
+    for stream in listener.incoming() {
+        let stream: TcpStream = stream?;
+        let reader = io::BufReader::new(stream.try_clone()?);
+        let tl = Arc::clone(&tl);
+        thread::spawn(move || log_disconnect(handle_client(reader, stream, &tl)));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn serve_unix<T: TodoLister + Send + Sync + 'static>(listener: UnixListener, tl: T) -> io::Result<()> {
+    let tl = Arc::new(ConcurrentTodoList::new(tl));
+    for stream in listener.incoming() {
+        let stream: UnixStream = stream?;
+        let reader = io::BufReader::new(stream.try_clone()?);
+        let tl = Arc::clone(&tl);
+        thread::spawn(move || log_disconnect(handle_client(reader, stream, &tl)));
+    }
+    Ok(())
+}
+
+//answers each query from a single client until it disconnects
+//(each client has its own search session, so a client typing ahead narrows its own previous results)
+pub fn handle_client<T: TodoLister + Send + Sync>(reader: impl BufRead, mut writer: impl Write, tl: &ConcurrentTodoList<T>) -> io::Result<()> {
+    let mut session = SearchSession::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim_end_matches('\r'); //tolerate clients which send crlf line endings
+        match tl.run_line_in_session(line, &mut session) {
+            Ok(r) => writeln!(writer, "{}", r)?,
+            Err(e) => writeln!(writer, "error: {}", e)?,
+        }
+        writeln!(writer)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+fn log_disconnect(result: io::Result<()>) {
+    if let Err(e) = result {
+        eprintln!("Client disconnected: {}", e);
+    }
+}
@@ -0,0 +1,78 @@
+//Type-ahead searching: a session remembers what its last search found, and when the next search only narrows it
+//(as "me", "mee", "meet" do) looks through those results instead of the whole list
+//Items pushed since the last search are checked too, so the list can keep changing between searches, even from other sessions
+//A tag edit can make an item match which didn't before, so after one the next search has to start from the whole list again:
+//whoever makes the edit resets the session, or for a list shared between sessions, each catches up with the list's count of edits
+
+use crate::*;
+use crate::profile;
+use crate::todo_list::{arrange, match_subsequence, matches};
+
+#[derive(Debug, Clone)]
+struct Previous {
+    sp: SearchParams,
+    found: Vec<Index>,
+    len: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchSession {
+    previous: Option<Previous>,
+    tag_edits: u64,
+}
+impl SearchSession {
+    pub fn new() -> Self {
+        SearchSession {
+            previous: None,
+            tag_edits: 0,
+        }
+    }
+
+    //results come back in the order the list first gave them, followed by any matching items pushed since
+    //(or sorted and paged as the search's options ask, every match is still remembered for the next search to narrow down)
+    pub fn search<'a, T: TodoLister>(&mut self, tl: &'a T, sp: SearchParams) -> Vec<&'a TodoItem> {
+        let sp = sp.normalised();
+        let results: Vec<&TodoItem> = match self.previous.take() {
+            Some(previous) if refines(&previous.sp, &sp) && previous.len <= tl.len() => {
+                let pushed = (previous.len..tl.len()).map(|i| Index::new(i as u64));
+                profile::count_candidates(previous.found.len() + tl.len() - previous.len);
+                previous.found.into_iter().chain(pushed)
+                    .filter_map(|idx| tl.get(idx))
+                    .filter(|item| matches(item, &sp))
+                    .collect()
+            },
+            _ => tl.search(sp.without_options()),
+        };
+        let found = results.iter().map(|item| item.index).collect();
+        let results = arrange(results, &sp);
+        self.previous = Some(Previous {
+            sp,
+            found,
+            len: tl.len(),
+        });
+        results
+    }
+
+    //the next search starts from the whole list again, as it has to after any tag edit
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    //for a list which counts the tag edits made to it, by whichever session made them:
+    //resets the session if the count has moved on since it was last caught up
+    pub fn catch_up(&mut self, tag_edits: u64) {
+        if tag_edits != self.tag_edits {
+            self.tag_edits = tag_edits;
+            self.reset();
+        }
+    }
+}
+
+//whether everything next finds is sure to have been found by previous, given normalised terms:
+//each previous term has to be a subsequence of some next term of the same kind, as then any word or tag matching that term matches it too
+pub fn refines(previous: &SearchParams, next: &SearchParams) -> bool {
+    previous.params.iter().all(|p| next.params.iter().any(|n| match (p, n) {
+        (SearchWordOrTag::RawWord(p), SearchWordOrTag::RawWord(n)) | (SearchWordOrTag::RawTag(p), SearchWordOrTag::RawTag(n)) => match_subsequence(n, p),
+        _ => false,
+    }))
+}
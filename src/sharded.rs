@@ -0,0 +1,118 @@
+//Naive scanning split across shards which are searched in parallel
+//Item i lives in shard i % n at position i / n, so each shard's results come back in index order
+//and merging them gives exactly what TodoList would have found
+
+use crate::*;
+use crate::memory::{self, MemoryUsage};
+use crate::profile;
+use crate::todo_list::{arrange, items_bytes, matches, TagCounts};
+
+use std::cmp::Reverse;
+use std::convert::TryFrom;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::thread;
+
+use rayon::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardedTodoList {
+    shards: Vec<Vec<TodoItem>>,
+    len: usize,
+    tag_counts: TagCounts,
+}
+impl ShardedTodoList {
+    //one shard per available cpu
+    pub fn new() -> Self {
+        Self::with_shards(thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    pub fn with_shards(shard_count: usize) -> Self {
+        assert!(shard_count > 0, "a sharded list needs at least one shard");
+        ShardedTodoList {
+            shards: vec![Vec::new(); shard_count],
+            len: 0,
+            tag_counts: TagCounts::default(),
+        }
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn position(&self, idx: Index) -> Option<(usize, usize)> {
+        let i = usize::try_from(idx.value()).ok().filter(|i| *i < self.len)?;
+        Some((i % self.shards.len(), i / self.shards.len()))
+    }
+}
+impl Default for ShardedTodoList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl TodoLister for ShardedTodoList {
+    fn push(&mut self, description: Vec<Word>, tags: Vec<Tag>) -> TodoItem {
+        let item = TodoItem::new(Index::new(self.len as u64), description, tags, false);
+        self.tag_counts.push(&item);
+        let shard_count = self.shards.len();
+        self.shards[self.len % shard_count].push(item.clone());
+        self.len += 1;
+        item
+    }
+    fn done_with_index(&mut self, idx: Index) -> Option<Index> {
+        let (shard, n) = self.position(idx)?;
+        self.tag_counts.mark_done(&mut self.shards[shard][n]);
+        Some(idx)
+    }
+    fn get(&self, idx: Index) -> Option<&TodoItem> {
+        let (shard, n) = self.position(idx)?;
+        Some(&self.shards[shard][n])
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn search(&self, sp: SearchParams) -> Vec<&TodoItem> {
+        let sp = sp.normalised();
+        profile::count_candidates(self.len); //counted here, since shards are searched on other threads' counters
+        let shard_results: Vec<Vec<&TodoItem>> = self.shards.par_iter()
+            .map(|shard| shard.iter().filter(|item| matches(item, &sp)).collect())
+            .collect();
+
+        //k-way merge of the sorted shard results, which can stop early when only the first few in index order are wanted
+        let needed = match sp.options.order() {
+            Some(SortOrder::Index) => sp.options.needed().unwrap_or(usize::MAX),
+            _ => usize::MAX,
+        };
+        let mut results = Vec::with_capacity(shard_results.iter().map(Vec::len).sum());
+        let mut shard_iters: Vec<_> = shard_results.into_iter().map(Vec::into_iter).collect();
+        let mut heads: BinaryHeap<_> = shard_iters.iter_mut().enumerate()
+            .filter_map(|(shard, iter)| iter.next().map(|item| Reverse((item.index, shard, item))))
+            .collect();
+        while let Some(Reverse((_, shard, item))) = heads.pop() {
+            if results.len() == needed {
+                break
+            }
+            results.push(item);
+            if let Some(next) = shard_iters[shard].next() {
+                heads.push(Reverse((next.index, shard, next)));
+            }
+        }
+        arrange(results, &sp)
+    }
+    fn stats(&self) -> ListStats {
+        self.tag_counts.stats()
+    }
+    fn tags(&self, prefix: &str) -> BTreeMap<String, usize> {
+        self.tag_counts.with_prefix(prefix)
+    }
+    fn edit_tags(&mut self, idx: Index, edit: &TagEdit) -> Option<bool> {
+        let (shard, n) = self.position(idx)?;
+        Some(self.tag_counts.edit(&mut self.shards[shard][n], edit))
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage{
+            items: self.shards.iter().map(items_bytes).sum(),
+            other: memory::vec_bytes(&self.shards),
+            ..MemoryUsage::default()
+        } + self.tag_counts.memory_usage()
+    }
+}
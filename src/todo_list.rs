@@ -1,11 +1,21 @@
 use std::fmt;
-use std::cmp::Ordering;
-use std::cell::RefCell;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap};
+use std::hash::Hash;
 use std::mem;
+use std::ops::Bound;
+use std::sync::{Mutex, PoisonError};
+
+use rustc_hash::FxHashSet;
+use serde::{Serialize, Serializer};
+use unicode_normalization::UnicodeNormalization;
 
 use crate::*;
+use crate::memory::{self, MemoryUsage};
+use crate::planner::PlannedTerm;
+use crate::profile;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub struct Index(u64);
 impl Index {
     pub fn new(i: u64) -> Self {
@@ -16,52 +26,83 @@ impl Index {
         self.0
     }
 }
-impl Ord for Index {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.value().cmp(&other.value())
-    }
-}
 impl fmt::Display for Index {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
+//compatibility-composed, case-folded form of a word or tag which all matching is done against,
+//so that "Café", "CAFÉ" and "cafe\u{301}" are indexed and searched identically
+pub fn normalise(s: &str) -> String {
+    s.nfkc().flat_map(char::to_lowercase).nfc().collect()
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Word(String);
+pub struct Word {
+    text: String,
+    key: String,
+}
 impl Word {
     pub fn new(s: &str) -> Self {
-        Word(s.to_owned())
+        Word {
+            text: s.to_owned(),
+            key: normalise(s),
+        }
     }
 
     pub fn value(&self) -> &str {
-        &self.0
+        &self.text
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+impl Serialize for Word { //as the text alone, the key can always be recomputed from it
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
     }
 }
 impl fmt::Display for Word {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.text)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Tag(String);
+pub struct Tag {
+    text: String,
+    key: String,
+}
 impl Tag {
     pub fn new(s: &str) -> Self {
-        Tag(s.to_owned())
+        Tag {
+            text: s.to_owned(),
+            key: normalise(s),
+        }
     }
 
     pub fn value(&self) -> &str {
-        &self.0
+        &self.text
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
     }
 
     pub fn from_strings(ss: Vec<&str>) -> Vec<Tag> {
-        ss.clone().into_iter().map(|s| Tag::new(s)).collect()
+        ss.into_iter().map(Tag::new).collect()
+    }
+}
+impl Serialize for Tag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
     }
 }
 impl fmt::Display for Tag {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "#{}", self.0)
+        write!(f, "#{}", self.text)
     }
 }
 
@@ -74,7 +115,7 @@ impl fmt::Display for Words<'_> {
         let mut display_string = String::new();
         for word in self.arr {
             display_string.push_str(&word.to_string());
-            display_string.push_str(" ");
+            display_string.push(' ');
         }
         display_string.pop();
         write!(f, "{}", display_string)
@@ -90,14 +131,14 @@ impl fmt::Display for Tags<'_> {
         let mut display_string = String::new();
         for tag in self.arr {
             display_string.push_str(&tag.to_string());
-            display_string.push_str(" ");
+            display_string.push(' ');
         }
         display_string.pop();
         write!(f, "{}", display_string)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct TodoItem {
     pub index: Index,
     pub description: Vec<Word>,
@@ -116,7 +157,7 @@ impl TodoItem {
 }
 impl PartialOrd for TodoItem {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.index.partial_cmp(&other.index)
+        Some(self.cmp(other))
     }
 }
 impl Ord for TodoItem {
@@ -130,114 +171,375 @@ impl fmt::Display for TodoItem {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SearchWordOrTag {
     RawWord (String),
     RawTag (String),
 }
+impl fmt::Display for SearchWordOrTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SearchWordOrTag::RawWord(w) => write!(f, "{}", w),
+            SearchWordOrTag::RawTag(t) => write!(f, "#{}", t),
+        }
+    }
+}
+
+//tags to take off an item and tags to put on it, both matched on their normalised keys
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TagEdit {
+    pub add: Vec<Tag>,
+    pub remove: Vec<Tag>,
+}
+impl TagEdit {
+    //removals go first, and a tag the item already has isn't added again, so whether anything changed is whether the keys did
+    pub(crate) fn apply(&self, tags: &mut Vec<Tag>) -> bool {
+        let before = tags.len();
+        tags.retain(|tag| !self.remove.iter().any(|r| r.key() == tag.key()));
+        let mut changed = tags.len() != before;
+        for tag in &self.add {
+            if !tags.iter().any(|t| t.key() == tag.key()) {
+                tags.push(tag.clone());
+                changed = true;
+            }
+        }
+        changed
+    }
+}
 
 pub trait TodoLister {
     fn push(&mut self, description: Vec<Word>, tags: Vec<Tag>) -> TodoItem;
     fn done_with_index(&mut self, idx: Index) -> Option<Index>;
     fn search(&self, sp: SearchParams) -> Vec<&TodoItem>;
+    fn get(&self, idx: Index) -> Option<&TodoItem>;
+    //items ever pushed, done or not, which is also the index the next push is given
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    //whether there's no room for another item, pushing onto a full list panics
+    //(only lists indexed by a trie with a MAX_ID of its own ever fill up)
+    fn is_full(&self) -> bool {
+        false
+    }
+    //how many items a search would find, without gathering them up
+    fn count(&self, sp: SearchParams) -> usize {
+        self.search(sp).len()
+    }
+    //backends keep their own TagCounts up to date and answer these from it, rather than looking through every item
+    fn stats(&self) -> ListStats {
+        TagCounts::of(self).stats()
+    }
+    //tags starting with a normalised prefix, with the number of open items carrying each
+    fn tags(&self, prefix: &str) -> BTreeMap<String, usize> {
+        TagCounts::of(self).with_prefix(prefix)
+    }
+    //changes an item's tags (done or not), giving back whether they changed, or None if there's no such item
+    fn edit_tags(&mut self, idx: Index, edit: &TagEdit) -> Option<bool>;
+    fn memory_usage(&self) -> MemoryUsage;
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+//open and done counts of all items and of the items with each tag key, for backends to keep up to date as items are pushed and done
+//(tags are kept sorted, so listing those with a prefix only visits them)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct TagCounts {
+    items: Counts,
+    tags: BTreeMap<String, Counts>,
+}
+impl TagCounts {
+    //counted afresh from every item in a list
+    fn of<T: TodoLister + ?Sized>(tl: &T) -> Self {
+        let mut counts = TagCounts::default();
+        for item in (0..tl.len() as u64).filter_map(|i| tl.get(Index::new(i))) {
+            counts.push(item);
+            if item.done {
+                counts.done(item);
+            }
+        }
+        counts
+    }
+
+    pub(crate) fn push(&mut self, item: &TodoItem) {
+        self.items.open += 1;
+        for key in tag_keys(item) {
+            self.tags.entry(key.to_owned()).or_default().open += 1;
+        }
+    }
+
+    //marks an item done, moving it over to the done counts if it was open until now
+    pub(crate) fn mark_done(&mut self, item: &mut TodoItem) {
+        if !item.done {
+            self.done(item);
+            item.done = true;
+        }
+    }
+
+    fn done(&mut self, item: &TodoItem) {
+        self.items.open -= 1;
+        self.items.done += 1;
+        for key in tag_keys(item) {
+            if let Some(counts) = self.tags.get_mut(key) {
+                counts.open -= 1;
+                counts.done += 1;
+            }
+        }
+    }
+
+    //edits an item's tags, moving its counts from the tags it had to the tags it has
+    //(a tag no item carries any more is dropped, as it would be if it had never been added)
+    pub(crate) fn edit(&mut self, item: &mut TodoItem, edit: &TagEdit) -> bool {
+        let before: Vec<String> = tag_keys(item).into_iter().map(str::to_owned).collect();
+        if !edit.apply(&mut item.tags) {
+            return false
+        }
+        for key in before {
+            if let Some(counts) = self.tags.get_mut(&key) {
+                if item.done { counts.done -= 1 } else { counts.open -= 1 }
+                if *counts == Counts::default() {
+                    self.tags.remove(&key);
+                }
+            }
+        }
+        for key in tag_keys(item) {
+            let counts = self.tags.entry(key.to_owned()).or_default();
+            if item.done { counts.done += 1 } else { counts.open += 1 }
+        }
+        true
+    }
+
+    pub(crate) fn stats(&self) -> ListStats {
+        ListStats {
+            items: self.items,
+            tags: self.tags.clone(),
+        }
+    }
+
+    pub(crate) fn with_prefix(&self, prefix: &str) -> BTreeMap<String, usize> {
+        self.tags.range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(|(tag, _)| tag.starts_with(prefix))
+            .map(|(tag, counts)| (tag.clone(), counts.open))
+            .collect()
+    }
+
+    pub(crate) fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            other: memory::btree_map_bytes(&self.tags) + self.tags.keys().map(String::capacity).sum::<usize>(),
+            ..MemoryUsage::default()
+        }
+    }
+}
+
+//an item tagged twice with the same tag is still only one item with that tag
+fn tag_keys(item: &TodoItem) -> Vec<&str> {
+    let mut keys: Vec<&str> = item.tags.iter().map(Tag::key).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys
+}
+
+//bytes held by a list of items, including their words' and tags' text and normalised keys
+pub(crate) fn items_bytes(items: &Vec<TodoItem>) -> usize {
+    let strings_bytes = |text: &String, key: &String| text.capacity() + key.capacity();
+    memory::vec_bytes(items) + items.iter().map(|item| {
+        memory::vec_bytes(&item.description) + memory::vec_bytes(&item.tags)
+            + item.description.iter().map(|w| strings_bytes(&w.text, &w.key)).sum::<usize>()
+            + item.tags.iter().map(|t| strings_bytes(&t.text, &t.key)).sum::<usize>()
+    }).sum::<usize>()
+}
+
+//matches characters rather than bytes, so multi-byte characters can only match whole characters
+pub(crate) fn match_subsequence(sequence: &str, subsequence: &str) -> bool {
+    let mut sub_chars = subsequence.chars();
+    let mut sub_char = sub_chars.next();
+    for c in sequence.chars() {
+        match sub_char {
+            None => return true,
+            Some(s) if s == c => sub_char = sub_chars.next(),
+            _ => (),
+        }
+    }
+    sub_char.is_none()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct TodoList {
     items: Vec<TodoItem>,
+    tag_counts: TagCounts,
 }
 impl TodoList {
     pub fn new() -> Self {
         TodoList {
             items: Vec::new(),
+            tag_counts: TagCounts::default(),
         }
     }
-    fn match_subsequence(sequence: &str, subsequence: &str) -> bool {
-        let mut sub_index = 0;
-        let sub_bytes = subsequence.as_bytes(); //this only splits on exact characters when we're using ASCII, not unicode
-        for byte in sequence.as_bytes().iter() {
-            if sub_index == subsequence.len() {
-                return true
-            }
-            unsafe { //safe because termination is guaranteed before index gets too large
-                if byte == sub_bytes.get_unchecked(sub_index) {
-                    sub_index += 1;
-                }
-            }
-        }
-        sub_index == subsequence.len()
-    }
 }
 impl TodoLister for TodoList {
     fn push(&mut self, description: Vec<Word>, tags: Vec<Tag>) -> TodoItem {
         let item = TodoItem::new(Index::new(self.items.len() as u64), description, tags, false);
+        self.tag_counts.push(&item);
         let item_c = item.clone();
         self.items.push(item);
         item_c
     }
     fn done_with_index(&mut self, idx: Index) -> Option<Index> {
         if let Ok(n) = self.items.binary_search_by_key(&idx, |item| item.index) {
-            self.items[n].done = true;
+            self.tag_counts.mark_done(&mut self.items[n]);
             Some(idx)
         }
         else {
             None
         }
     }
+    fn get(&self, idx: Index) -> Option<&TodoItem> {
+        self.items.binary_search_by_key(&idx, |item| item.index).ok().map(|n| &self.items[n])
+    }
+    fn len(&self) -> usize {
+        self.items.len()
+    }
     fn search(&self, sp: SearchParams) -> Vec<&TodoItem> {
-        let mut results = Vec::new();
-        'item: for item in self.items.iter() { 
-            if item.done { //don't search done items
-                continue 'item
+        let sp = sp.normalised();
+        let mut tested = 0;
+        let found = self.items.iter().filter(|item| {
+            tested += 1;
+            matches(item, &sp)
+        });
+        //items are kept in index order, so a page in either direction stops scanning once it's full
+        let results = match sp.options.order() {
+            Some(SortOrder::Index) => page(found, &sp.options),
+            Some(SortOrder::IndexDescending) => page(found.rev(), &sp.options),
+            _ => arrange(found.collect(), &sp),
+        };
+        profile::count_candidates(tested);
+        results
+    }
+    fn stats(&self) -> ListStats {
+        self.tag_counts.stats()
+    }
+    fn tags(&self, prefix: &str) -> BTreeMap<String, usize> {
+        self.tag_counts.with_prefix(prefix)
+    }
+    fn edit_tags(&mut self, idx: Index, edit: &TagEdit) -> Option<bool> {
+        let n = self.items.binary_search_by_key(&idx, |item| item.index).ok()?;
+        Some(self.tag_counts.edit(&mut self.items[n], edit))
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage{items: items_bytes(&self.items), ..MemoryUsage::default()} + self.tag_counts.memory_usage()
+    }
+}
+
+//how closely an open item matches normalised search terms, higher is better: each term scores its closest match among the
+//item's words (or tags), 3 for the whole word, 2 for a prefix, 1 for letters in a row and 0 for letters spread out
+pub fn score(item: &TodoItem, sp: &SearchParams) -> u32 {
+    let closeness = |key: &str, term: &str| {
+        if !match_subsequence(key, term) { None }
+        else if key == term { Some(3) }
+        else if key.starts_with(term) { Some(2) }
+        else if key.contains(term) { Some(1) }
+        else { Some(0) }
+    };
+    sp.params.iter().map(|param| match param {
+        SearchWordOrTag::RawWord(sw) => item.description.iter().filter_map(|w| closeness(w.key(), sw)).max(),
+        SearchWordOrTag::RawTag(st) => item.tags.iter().filter_map(|t| closeness(t.key(), st)).max(),
+    }.unwrap_or(0)).sum()
+}
+
+//puts every item a search found in the order its (normalised) search params ask for, and cuts out the page asked for
+//(with no options, results are left in the order the backend found them)
+pub fn arrange<'a>(mut results: Vec<&'a TodoItem>, sp: &SearchParams) -> Vec<&'a TodoItem> {
+    profile::count_gathered(results.len());
+    match sp.options.order() {
+        None => return results,
+        Some(SortOrder::Index) => results.sort_unstable_by_key(|item| item.index),
+        Some(SortOrder::IndexDescending) => results.sort_unstable_by_key(|item| Reverse(item.index)),
+        Some(SortOrder::Score) => results.sort_by_cached_key(|item| (Reverse(score(item, sp)), item.index)),
+    }
+    page(results.into_iter(), &sp.options)
+}
+
+//the page asked for out of results which are already in order
+pub(crate) fn page<'a>(sorted: impl Iterator<Item = &'a TodoItem>, options: &SearchOptions) -> Vec<&'a TodoItem> {
+    sorted.skip(options.offset.unwrap_or(0)).take(options.limit.unwrap_or(usize::MAX)).collect()
+}
+
+//the n smallest values in order, only ever holding n of them rather than sorting them all
+//(values may come more than once, and are only held once)
+fn smallest<T: Ord + Hash + Copy>(values: impl Iterator<Item = T>, n: usize) -> Vec<T> {
+    let mut heap = BinaryHeap::with_capacity(n);
+    let mut held = FxHashSet::default();
+    for value in values {
+        if held.contains(&value) {
+            continue
+        }
+        if heap.len() < n {
+            heap.push(value);
+            held.insert(value);
+        }
+        else if let Some(mut largest) = heap.peek_mut() {
+            if value < *largest {
+                held.remove(&*largest);
+                held.insert(value);
+                *largest = value;
             }
-            'param: for param in &sp.params { 
-                match param {
-                    SearchWordOrTag::RawWord(sw) => {
-                        for Word(w) in &item.description {
-                            if Self::match_subsequence(w, sw) {
-                                continue 'param //successful match, try next search parameter
-                            }
-                        }
-                        continue 'item //failed to match with any word in description, try next item
+        }
+    }
+    heap.into_sorted_vec()
+}
+
+//whether an item is open and matches every one of a set of normalised search parameters
+pub(crate) fn matches(item: &TodoItem, sp: &SearchParams) -> bool {
+    if item.done { //don't search done items
+        return false
+    }
+    'param: for param in &sp.params { 
+        match param {
+            SearchWordOrTag::RawWord(sw) => {
+                for word in &item.description {
+                    if match_subsequence(word.key(), sw) {
+                        continue 'param //successful match, try next search parameter
                     }
-                    SearchWordOrTag::RawTag(st) => {
-                        for Tag(t) in &item.tags {
-                            if Self::match_subsequence(t, st) {
-                                continue 'param //successful match, try next search parameter
-                            }
-                        }
-                        continue 'item //failed to match with any tag, try next item
+                }
+                return false //failed to match with any word in description
+            }
+            SearchWordOrTag::RawTag(st) => {
+                for tag in &item.tags {
+                    if match_subsequence(tag.key(), st) {
+                        continue 'param //successful match, try next search parameter
                     }
                 }
+                return false //failed to match with any tag
             }
-            results.push(item); //successfully matched every seach parameter, add to results
         }
-        results
     }
+    true //successfully matched every seach parameter
 }
 
 //with previous match filtering
-#[derive(Debug, Clone, PartialEq, Eq)]
+//(the results buffer is behind a mutex rather than a RefCell so that the list stays Sync, searches which find it in use just allocate their own)
+#[derive(Debug)]
 pub struct TodoList2 {
     items: Vec<TodoItem>,
-    item_refs: RefCell<Option<Vec<usize>>>,
+    item_refs: Mutex<Option<Vec<usize>>>,
+    tag_counts: TagCounts,
 }
 impl TodoList2 {
     pub fn new() -> Self {
         TodoList2 {
             items: Vec::new(),
-            item_refs: RefCell::new(Some(Vec::new())),
+            item_refs: Mutex::new(Some(Vec::new())),
+            tag_counts: TagCounts::default(),
         }
     }
     fn search_initial<'a>(&'a self, item_refs: &mut Vec<&'a TodoItem>, search: SearchWordOrTag) {
+        profile::count_candidates(self.items.len());
         match search {
             SearchWordOrTag::RawWord(subsequence) => {
                 for item in &self.items {
                     if item.done {
                         continue
                     }
-                    for Word(sequence) in &item.description {
-                        if Self::match_subsequence(&sequence, &subsequence) {
+                    for word in &item.description {
+                        if match_subsequence(word.key(), &subsequence) {
                             item_refs.push(item);
                             break
                         }
@@ -249,8 +551,8 @@ impl TodoList2 {
                     if item.done {
                         continue
                     }
-                    for Tag(sequence) in &item.tags {
-                        if Self::match_subsequence(&sequence, &subsequence) {
+                    for tag in &item.tags {
+                        if match_subsequence(tag.key(), &subsequence) {
                             item_refs.push(item);
                             break
                         }
@@ -260,14 +562,15 @@ impl TodoList2 {
         }
     }
     fn search_filter(self: &TodoList2, refs: &mut Vec<&TodoItem>, search: SearchWordOrTag) {
+        profile::count_candidates(refs.len());
         match search {
             SearchWordOrTag::RawWord(subsequence) => {
                 refs.retain(|item| {
                     if item.done {
                         return false
                     }
-                    for Word(sequence) in &item.description {
-                        if Self::match_subsequence(&sequence, &subsequence) {
+                    for word in &item.description {
+                        if match_subsequence(word.key(), &subsequence) {
                             return true
                         }
                     }
@@ -279,8 +582,8 @@ impl TodoList2 {
                     if item.done {
                         return false
                     }
-                    for Tag(sequence) in &item.tags {
-                        if Self::match_subsequence(&sequence, &subsequence) {
+                    for tag in &item.tags {
+                        if match_subsequence(tag.key(), &subsequence) {
                             return true
                         }
                     }
@@ -289,49 +592,59 @@ impl TodoList2 {
             },
         }
     }
-    fn match_subsequence(sequence: &str, subsequence: &str) -> bool {
-        let mut sub_index = 0;
-        let sub_bytes = subsequence.as_bytes(); //this only splits on exact characters when we're using ASCII, not unicode
-        for byte in sequence.as_bytes().iter() {
-            if sub_index == subsequence.len() {
-                return true
-            }
-            // if sequence.len() - seq_index < subsequence.len() - sub_index { //length checking added here (seems to make it slightly slower overall)
-            //     return false
-            // }
-            unsafe { //safe because termination is guaranteed before index gets too large
-                if byte == sub_bytes.get_unchecked(sub_index) {
-                    sub_index += 1;
-                }
-            }
+}
+impl Default for TodoList2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Clone for TodoList2 {
+    fn clone(&self) -> Self {
+        TodoList2 {
+            items: self.items.clone(),
+            item_refs: Mutex::new(Some(Vec::new())),
+            tag_counts: self.tag_counts.clone(),
         }
-        sub_index == subsequence.len()
     }
 }
-impl<'a> TodoLister for TodoList2 {
+impl PartialEq for TodoList2 { //the results buffer is scratch space, so only items count
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+impl Eq for TodoList2 {}
+impl TodoLister for TodoList2 {
     fn push(&mut self, description: Vec<Word>, tags: Vec<Tag>) -> TodoItem {
         let item = TodoItem::new(Index::new(self.items.len() as u64), description, tags, false);
+        self.tag_counts.push(&item);
         let item_c = item.clone();
         self.items.push(item);
         item_c
     }
     fn done_with_index(&mut self, idx: Index) -> Option<Index> {
         if let Ok(n) = self.items.binary_search_by_key(&idx, |item| item.index) {
-            self.items[n].done = true;
+            self.tag_counts.mark_done(&mut self.items[n]);
             Some(idx)
         }
         else {
             None
         }
     }
+    fn get(&self, idx: Index) -> Option<&TodoItem> {
+        self.items.binary_search_by_key(&idx, |item| item.index).ok().map(|n| &self.items[n])
+    }
+    fn len(&self) -> usize {
+        self.items.len()
+    }
     fn search(&self, sp: SearchParams) -> Vec<&TodoItem> {
         //get item_refs
-        let entry: &mut Option<Vec<usize>> = &mut self.item_refs.borrow_mut();
-        let item_refs: Vec<usize> = mem::take(entry).unwrap();
+        let mut entry = self.item_refs.try_lock().ok();
+        let item_refs: Vec<usize> = entry.as_mut().and_then(|entry| mem::take(&mut **entry)).unwrap_or_default();
         let mut item_refs: Vec<&TodoItem> = item_refs.into_iter().filter_map(|_| None).collect(); //should not cause a realloc
 
         //add and filter references
-        let mut params = sp.params.into_iter();
+        let sp = sp.normalised();
+        let mut params = sp.params.iter().cloned();
         if let Some(first_param) = params.next() {
             self.search_initial(&mut item_refs, first_param);
             for param in params {
@@ -344,10 +657,30 @@ impl<'a> TodoLister for TodoList2 {
 
         //put item_refs back
         let item_refs: Vec<usize> = item_refs.into_iter().filter_map(|_| None).collect();
-        *entry = Some(item_refs);
+        if let Some(mut entry) = entry {
+            *entry = Some(item_refs);
+        }
 
         //return results
-        results
+        arrange(results, &sp)
+    }
+    fn stats(&self) -> ListStats {
+        self.tag_counts.stats()
+    }
+    fn tags(&self, prefix: &str) -> BTreeMap<String, usize> {
+        self.tag_counts.with_prefix(prefix)
+    }
+    fn edit_tags(&mut self, idx: Index, edit: &TagEdit) -> Option<bool> {
+        let n = self.items.binary_search_by_key(&idx, |item| item.index).ok()?;
+        Some(self.tag_counts.edit(&mut self.items[n], edit))
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        let item_refs = self.item_refs.lock().unwrap_or_else(PoisonError::into_inner);
+        MemoryUsage{
+            items: items_bytes(&self.items),
+            other: item_refs.as_ref().map_or(0, memory::vec_bytes),
+            ..MemoryUsage::default()
+        } + self.tag_counts.memory_usage()
     }
 }
 
@@ -356,6 +689,7 @@ pub struct TriedoList<T: Trie + Default> {
     items: Vec<TodoItem>,
     words: T,
     tags: T,
+    tag_counts: TagCounts,
 }
 impl<T: Trie + Default> TriedoList<T> {
     pub fn new() -> Self {
@@ -363,55 +697,130 @@ impl<T: Trie + Default> TriedoList<T> {
             items: Vec::new(),
             words: T::default(),
             tags: T::default(),
+            tag_counts: TagCounts::default(),
         }
     }
+
+    //hands the ids of the open items matching every one of a set of normalised search parameters on to found
+    //(each term searches only among the ids which matched every term before it, and the last term's trie hands its ids on
+    //one at a time to be checked against those, so the ids matching every term are never all held at once;
+    //an id can be handed on more than once though, see Trie::search_ids)
+    fn matching_ids<R>(&self, sp: &SearchParams, found: impl FnOnce(&mut dyn Iterator<Item = u64>) -> R) -> R {
+        let mut plan = planner::plan(sp, &self.words, &self.tags);
+        let last = plan.pop();
+        let mut indices: Option<FxHashSet<u64>> = None;
+        for planned in &plan {
+            let mut found = self.term_ids(planned, indices.as_ref());
+            if let Some(indices) = &indices { //not every trie prunes by the filter it's given
+                found.retain(|id| indices.contains(id));
+            }
+            profile::count_gathered(found.len());
+            let none_left = found.is_empty();
+            indices = Some(found);
+            if none_left {
+                break
+            }
+        }
+        match last {
+            Some(last) if indices.as_ref().is_none_or(|indices| !indices.is_empty()) => {
+                let trie = if last.is_tag { &self.tags } else { &self.words };
+                let mut ids = trie.search_ids(last.term, indices.as_ref())
+                    .inspect(|_| profile::count_candidates(1))
+                    .filter(|id| indices.as_ref().is_none_or(|indices| indices.contains(id)));
+                found(&mut ids)
+            },
+            _ => found(&mut std::iter::empty()),
+        }
+    }
+
+    //the ids a term's trie hands back, some of which may not have passed the filter
+    fn term_ids(&self, planned: &PlannedTerm, filter: Option<&FxHashSet<u64>>) -> FxHashSet<u64> {
+        let trie = if planned.is_tag { &self.tags } else { &self.words };
+        let found = trie.search(vec![planned.term], filter);
+        profile::count_candidates(found.len());
+        found
+    }
+}
+impl<T: Trie + Default> Default for TriedoList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 impl<T: Trie + Default> TodoLister for TriedoList<T> {
     fn push(&mut self, description: Vec<Word>, tags: Vec<Tag>) -> TodoItem {
-        self.words.add(self.items.len() as u64, description.iter().map(|Word(s)| &s[..]).collect());
-        self.tags.add(self.items.len() as u64, tags.iter().map(|Tag(t)| &t[..]).collect());
+        self.words.add(self.items.len() as u64, description.iter().map(Word::key).collect());
+        self.tags.add(self.items.len() as u64, tags.iter().map(Tag::key).collect());
         let item = TodoItem::new(Index::new(self.items.len() as u64), description, tags, false);
+        self.tag_counts.push(&item);
         let item_c = item.clone();
         self.items.push(item);
         item_c
     }
     fn done_with_index(&mut self, idx: Index) -> Option<Index> {
         self.words.delete(idx.value());
+        self.tags.delete(idx.value());
         if let Ok(n) = self.items.binary_search_by_key(&idx, |item| item.index) {
-            self.items[n].done = true;
+            self.tag_counts.mark_done(&mut self.items[n]);
             Some(idx)
         }
         else {
             None
         }
     }
+    fn get(&self, idx: Index) -> Option<&TodoItem> {
+        self.items.binary_search_by_key(&idx, |item| item.index).ok().map(|n| &self.items[n])
+    }
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+    //ids go up to the index of the last item
+    fn is_full(&self) -> bool {
+        self.items.len() as u64 > T::MAX_ID
+    }
     fn search(&self, sp: SearchParams) -> Vec<&TodoItem> {
-        let mut word_searches = Vec::new();
-        let mut tag_searches = Vec::new();
-        for param in &sp.params {
-            match param {
-                SearchWordOrTag::RawWord(w) => {
-                    word_searches.push(&w[..]);
-                },
-                SearchWordOrTag::RawTag(t) => {
-                    tag_searches.push(&t[..]);
-                },
-            }
-        }
-        let indices;
-        if word_searches.len() > 0 && tag_searches.len() > 0 {
-            let word_indices = self.words.search(word_searches, None);
-            indices = word_indices.intersection(&self.tags.search(tag_searches, Some(&word_indices))).cloned().collect();
-        }
-        else if word_searches.len() > 0 {
-            indices = self.words.search(word_searches, None);
-        }
-        else if tag_searches.len() > 0 {
-            indices = self.tags.search(tag_searches, None);
+        let sp = sp.normalised();
+        let item = |index: u64| &self.items[index as usize];
+        //when only the first page or so is wanted in index order, pick out just those ids rather than sorting every match
+        self.matching_ids(&sp, |indices| match (sp.options.order(), sp.options.needed()) {
+            (Some(SortOrder::Index), Some(needed)) => page(smallest(indices, needed).into_iter().map(item), &sp.options),
+            (Some(SortOrder::IndexDescending), Some(needed)) => {
+                page(smallest(indices.map(Reverse), needed).into_iter().map(|Reverse(index)| item(index)), &sp.options)
+            },
+            _ => arrange(indices.collect::<FxHashSet<u64>>().into_iter().map(item).collect(), &sp),
+        })
+    }
+    //answered from the matching ids alone, though they're gathered to count each only once
+    fn count(&self, sp: SearchParams) -> usize {
+        let sp = sp.normalised();
+        let found = self.matching_ids(&sp, |indices| indices.collect::<FxHashSet<u64>>().len());
+        profile::count_gathered(found);
+        sp.options.page_len(found)
+    }
+    fn stats(&self) -> ListStats {
+        self.tag_counts.stats()
+    }
+    fn tags(&self, prefix: &str) -> BTreeMap<String, usize> {
+        self.tag_counts.with_prefix(prefix)
+    }
+    //only the tags which came or went are added to or removed from the tags trie for an open item, done items are already out of the tries
+    fn edit_tags(&mut self, idx: Index, edit: &TagEdit) -> Option<bool> {
+        let n = self.items.binary_search_by_key(&idx, |item| item.index).ok()?;
+        let item = &mut self.items[n];
+        let before: Vec<String> = tag_keys(item).into_iter().map(str::to_owned).collect();
+        if !self.tag_counts.edit(item, edit) {
+            return Some(false)
         }
-        else {
-            return Vec::new() 
+        if !item.done {
+            let after = tag_keys(item);
+            let removed = before.iter().map(String::as_str).filter(|key| !after.contains(key)).collect();
+            let added = after.iter().copied().filter(|key| !before.iter().any(|b| b == key)).collect();
+            self.tags.remove(idx.value(), removed, after.clone());
+            self.tags.add(idx.value(), added);
         }
-        indices.iter().map(|index| &self.items[*index as usize]).collect()
+        Some(true)
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage{items: items_bytes(&self.items), ..MemoryUsage::default()} + self.words.memory_usage() + self.tags.memory_usage()
+            + self.tag_counts.memory_usage()
     }
 }
\ No newline at end of file
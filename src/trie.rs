@@ -1,13 +1,290 @@
 //Various experiments building an efficient Trie to replace Naive implementation
 
-use rustc_hash::{FxHashSet, FxHashMap};
+//inserts and searches are normalised strings of any characters, children are keyed by char so the alphabet is open-ended
+//(slicing past a matched character has to step over its full utf-8 length rather than a single byte)
+
+use crate::memory::{self, MemoryUsage};
+use crate::profile;
+
+use std::convert::TryFrom;
+use std::mem::size_of;
 
-const CHARS: [char; 27] = ['a','b','c','d','e','f','g','h','i','j','k','l','m','n','o','p','q','r','s','t','u','v','w','x','y','z','-'];
+use roaring::RoaringBitmap;
+use rustc_hash::{FxHashSet, FxHashMap};
 
 pub trait Trie { 
+    //the highest id the trie can hold, adding a higher one panics
+    const MAX_ID: u64 = u64::MAX;
     fn add(&mut self, id: u64, inserts: Vec<&str>);
     fn search(&self, searches: Vec<&str>, filter: Option<&FxHashSet<u64>>) -> FxHashSet<u64>;
     fn delete(&mut self, id: u64);
+    //takes an id back out of the nodes on some of the strings it was added with, the other strings it was added with being kept
+    //(nodes on the way to a kept string keep the id, as searches passing through them still have to find it)
+    fn remove(&mut self, id: u64, removes: Vec<&str>, kept: Vec<&str>);
+    fn memory_usage(&self) -> MemoryUsage;
+    //rough count of ids a search would find, for ordering searches cheapest first:
+    //the ids at the deepest node reached by following the search's characters down from the root,
+    //so longer searches and searches starting with rarer letters come out smaller
+    //(the first-letter node alone barely tells searches apart, since a search can match from anywhere in a word,
+    //and for the same reason a search leading nowhere is bounded by where it stopped rather than taken to match nothing)
+    fn estimate(&self, search: &str) -> u64;
+    //the ids a single search finds, handed on node by node as the search is used up rather than gathered into a set first,
+    //so an id passing through more than one of those nodes is handed on once for each (the filter only prunes, as in search)
+    fn search_ids<'a>(&'a self, search: &'a str, filter: Option<&'a FxHashSet<u64>>) -> Box<dyn Iterator<Item = u64> + 'a> {
+        Box::new(self.search(vec![search], filter).into_iter())
+    }
+}
+
+//children are stored inside their parent's map, so they're counted as nodes rather than as part of the map
+fn children_map_bytes<T>(children: &FxHashMap<char, T>) -> usize {
+    memory::hash_map_bytes(children) - children.len() * size_of::<T>()
+}
+
+//roaring doesn't expose the size of its containers, each is a u16 key beside a vec or a boxed bitset, so roughly 32 bytes
+fn bitmap_bytes(ids: &RoaringBitmap) -> usize {
+    let stats = ids.statistics();
+    let container_bytes = stats.n_bytes_array_containers + stats.n_bytes_run_containers + stats.n_bytes_bitset_containers;
+    container_bytes as usize + stats.n_containers as usize * 32
+}
+
+//how far below a node at prefix the longest kept string starting with it goes, or None if the id needs no kept string through the node
+fn kept_depth(kept: &[&str], prefix: &str) -> Option<usize> {
+    kept.iter().filter(|k| k.starts_with(prefix)).map(|k| k[prefix.len()..].chars().count()).max()
+}
+
+//the byte length of each prefix of a string, from the empty one at the root down to the whole string, for walking its nodes
+fn prefix_ends(s: &str) -> impl Iterator<Item = (usize, Option<char>)> + '_ {
+    s.char_indices().map(|(i, c)| (i, Some(c))).chain(std::iter::once((s.len(), None)))
+}
+
+//what the code shared between the tries needs from their nodes, however each keeps its ids
+trait Node: Sized {
+    fn child(&self, c: char) -> Option<&Self>;
+    fn child_mut(&mut self, c: char) -> Option<&mut Self>;
+    fn id_count(&self) -> u64;
+    //takes an id out of this node alone, leaving its children as they are
+    fn remove_id(&mut self, id: u64);
+    fn children(&self) -> &FxHashMap<char, Self>;
+    fn ids(&self) -> Box<dyn Iterator<Item = u64> + '_>;
+    fn has_id(&self, id: u64) -> bool;
+}
+
+//the ids at the deepest node a search leads down to from the root, the root itself if no string starts with its first character
+fn estimate_ids<N: Node>(root: &N, search: &str) -> u64 {
+    let mut trie = root;
+    for c in search.chars() {
+        match trie.child(c) {
+            Some(child) => trie = child,
+            None => break,
+        }
+    }
+    trie.id_count()
+}
+
+//Trie::search_ids, walking the nodes as search_single does but handing on each used up search's ids as it's reached
+//(tries which don't prune by the filter in search aren't given one here either)
+struct NodeIds<'a, N> {
+    tries_to_visit: Vec<(&'a N, &'a str)>,
+    filter: Option<&'a FxHashSet<u64>>,
+    ids: Box<dyn Iterator<Item = u64> + 'a>,
+}
+impl<'a, N: Node> NodeIds<'a, N> {
+    fn new(root: &'a N, search: &'a str, filter: Option<&'a FxHashSet<u64>>) -> Self {
+        NodeIds{
+            tries_to_visit: vec![(root, search)],
+            filter,
+            ids: Box::new(std::iter::empty()),
+        }
+    }
+}
+impl<'a, N: Node> Iterator for NodeIds<'a, N> {
+    type Item = u64;
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            if let Some(id) = self.ids.next() {
+                return Some(id)
+            }
+            let (trie, search) = self.tries_to_visit.pop()?;
+            profile::count_nodes_visited(1);
+            if let Some(f) = self.filter {
+                if !f.iter().any(|id| trie.has_id(*id)) { //no id in the filter passes through this trie, so skip this branch
+                    continue
+                }
+            }
+            if let Some(first_char) = search.chars().next() {
+                for (c, new_trie) in trie.children().iter() {
+                    let new_search = if *c == first_char { &search[first_char.len_utf8()..] } else { search };
+                    self.tries_to_visit.push((new_trie, new_search));
+                }
+            }
+            else {
+                self.ids = trie.ids();
+            }
+        }
+    }
+}
+
+//Trie::remove for tries whose nodes only record which ids pass through them
+fn remove_ids<N: Node>(root: &mut N, id: u64, removes: Vec<&str>, kept: Vec<&str>) {
+    for remove in removes {
+        let mut trie = &mut *root;
+        for (end, next) in prefix_ends(remove) {
+            if kept_depth(&kept, &remove[..end]).is_none() {
+                trie.remove_id(id);
+            }
+            let child = match next {
+                Some(c) => trie.child_mut(c),
+                None => None,
+            };
+            match child {
+                Some(child) => trie = child,
+                None => break,
+            }
+        }
+    }
+}
+
+//non-recursive, search-match pruning, with ids kept in compressed bitmaps (so ids have to fit in a u32, see MAX_ID)
+//bitmaps take 2 bytes per id for sparse nodes and 1 bit per id for dense ones, against 8+ bytes per id in an FxHashSet,
+//and unions and intersections work a container of ids at a time rather than hashing each one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trie5 {
+    children: FxHashMap<char, Trie5>,
+    ids: RoaringBitmap,
+}
+impl Trie5 {
+    fn new() -> Self {
+        Trie5{
+            children: FxHashMap::default(),
+            ids: RoaringBitmap::new(),
+        }
+    }
+    fn bitmap_id(id: u64) -> u32 {
+        u32::try_from(id).expect("Trie5 only holds ids up to Trie5::MAX_ID")
+    }
+    fn add_single(&mut self, id: u32, insert: &str) {
+        let mut trie = self;
+        trie.ids.insert(id);
+        for c in insert.chars() {
+            trie = trie.children.entry(c).or_default();
+            trie.ids.insert(id);
+        }
+    }
+    fn search_single(&self, search: &str, filter: Option<&RoaringBitmap>) -> RoaringBitmap {
+        let mut results = RoaringBitmap::new();
+        let mut tries_to_visit = vec![(self, search)];
+        let mut visited = 0;
+        while let Some((trie, search)) = tries_to_visit.pop() {
+            visited += 1;
+            if let Some(f) = filter {
+                if trie.ids.is_disjoint(f) { //no id in the filter passes through this trie, so skip this branch
+                    continue
+                }
+            }
+            if let Some(first_char) = search.chars().next() {
+                for (c, new_trie) in trie.children.iter() {
+                    let new_search = if *c == first_char { &search[first_char.len_utf8()..] } else { search };
+                    tries_to_visit.push((new_trie, new_search));
+                }
+            }
+            else {
+                results |= &trie.ids;
+            }
+        }
+        profile::count_nodes_visited(visited);
+        results
+    }
+}
+impl Trie for Trie5 {
+    const MAX_ID: u64 = u32::MAX as u64;
+    fn add(&mut self, id: u64, inserts: Vec<&str>) {
+        let id = Self::bitmap_id(id);
+        for insert in inserts {
+            Self::add_single(self, id, insert)
+        }
+    }
+    fn search(&self, searches: Vec<&str>, filter: Option<&FxHashSet<u64>>) -> FxHashSet<u64> {
+        let filter: Option<RoaringBitmap> = filter.map(|f| f.iter().filter_map(|id| u32::try_from(*id).ok()).collect());
+        let mut searches = searches.iter();
+        if let Some(first_search) = searches.next() {
+            let mut result = Self::search_single(self, first_search, filter.as_ref());
+            for search in searches { //use results of previous searches to filter ids in subsequent searches
+                if result.is_empty() {
+                    break
+                }
+                result &= Self::search_single(self, search, Some(&result));
+            }
+            result.iter().map(u64::from).collect()
+        }
+        else {
+            FxHashSet::default()
+        }
+    }
+    fn delete(&mut self, id: u64) {
+        let id = match u32::try_from(id) {
+            Ok(id) => id,
+            Err(_) => return, //can never have been added
+        };
+        let mut tries_to_visit = vec![self];
+        while let Some(trie) = tries_to_visit.pop() {
+            if trie.ids.remove(id) {
+                for new_trie in trie.children.values_mut() {
+                    tries_to_visit.push(new_trie)
+                }
+            }
+        }
+    }
+    fn remove(&mut self, id: u64, removes: Vec<&str>, kept: Vec<&str>) {
+        remove_ids(self, id, removes, kept)
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage::default();
+        let mut tries_to_visit = vec![self];
+        while let Some(trie) = tries_to_visit.pop() {
+            usage.node_count += 1;
+            usage.nodes += size_of::<Self>();
+            usage.maps += children_map_bytes(&trie.children);
+            usage.id_sets += bitmap_bytes(&trie.ids);
+            tries_to_visit.extend(trie.children.values());
+        }
+        usage
+    }
+    fn estimate(&self, search: &str) -> u64 {
+        estimate_ids(self, search)
+    }
+    fn search_ids<'a>(&'a self, search: &'a str, filter: Option<&'a FxHashSet<u64>>) -> Box<dyn Iterator<Item = u64> + 'a> {
+        Box::new(NodeIds::new(self, search, filter))
+    }
+}
+impl Default for Trie5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Node for Trie5 {
+    fn child(&self, c: char) -> Option<&Self> {
+        self.children.get(&c)
+    }
+    fn child_mut(&mut self, c: char) -> Option<&mut Self> {
+        self.children.get_mut(&c)
+    }
+    fn id_count(&self) -> u64 {
+        self.ids.len()
+    }
+    fn remove_id(&mut self, id: u64) {
+        if let Ok(id) = u32::try_from(id) { //otherwise it can never have been added
+            self.ids.remove(id);
+        }
+    }
+    fn children(&self) -> &FxHashMap<char, Self> {
+        &self.children
+    }
+    fn ids(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        Box::new(self.ids.iter().map(u64::from))
+    }
+    fn has_id(&self, id: u64) -> bool {
+        u32::try_from(id).is_ok_and(|id| self.ids.contains(id))
+    }
 }
 
 //non-recursive, search-match pruning and depth pruning
@@ -25,7 +302,7 @@ impl Trie4 {
     }
     fn add_single(&mut self, id: u64, insert: &str) {
         let mut trie = self;
-        let mut new_depth = insert.len();
+        let mut new_depth = insert.chars().count();
         
         trie.id_to_depth.entry(id)
             .and_modify(|current_depth| {
@@ -36,7 +313,7 @@ impl Trie4 {
             .or_insert(new_depth);
 
         for c in insert.chars() {
-            trie = trie.children.entry(c).or_insert(Trie4::new());
+            trie = trie.children.entry(c).or_default();
             new_depth -= 1;
 
             trie.id_to_depth.entry(id)
@@ -51,7 +328,9 @@ impl Trie4 {
     fn search_single(&self, search: &str, filter: Option<&FxHashSet<u64>>) -> FxHashSet<u64> {
         let mut results = FxHashSet::default();
         let mut tries_to_visit = vec![(self, search)];
+        let mut visited = 0;
         'trie: while let Some((trie, search)) = tries_to_visit.pop() {
+            visited += 1;
 
             let max_depth;
             if let Some(filter) = filter {
@@ -64,7 +343,7 @@ impl Trie4 {
                 max_depth = trie.id_to_depth.values().max();
             }
             if let Some(max_depth) = max_depth {
-                if search.len() > *max_depth { //match is impossible because trie is not deep enough, so skip
+                if search.chars().count() > *max_depth { //match is impossible because trie is not deep enough, so skip
                     continue 'trie
                 }
             }
@@ -72,12 +351,10 @@ impl Trie4 {
                 continue 'trie
             }
 
-            if let Some(first_char) = search.chars().nth(0) {
-                for c in CHARS.iter() {
-                    if let Some(new_trie) = trie.children.get(c) {
-                        let new_search = if *c == first_char { &search[1..] } else { search };
-                        tries_to_visit.push((new_trie, new_search));
-                    }
+            if let Some(first_char) = search.chars().next() {
+                for (c, new_trie) in trie.children.iter() {
+                    let new_search = if *c == first_char { &search[first_char.len_utf8()..] } else { search };
+                    tries_to_visit.push((new_trie, new_search));
                 }
             }
             else {
@@ -86,6 +363,7 @@ impl Trie4 {
             }
 
         }
+        profile::count_nodes_visited(visited);
         results
     }
 }
@@ -111,19 +389,79 @@ impl Trie for Trie4 {
     fn delete(&mut self, id: u64) {
         let mut tries_to_visit = vec![self];
         while let Some(trie) = tries_to_visit.pop() {
-            if let Some(_) = trie.id_to_depth.remove(&id) {
+            if trie.id_to_depth.remove(&id).is_some() {
                 for new_trie in trie.children.values_mut() {
                     tries_to_visit.push(new_trie)
                 }
             }
         }
     }
+    fn remove(&mut self, id: u64, removes: Vec<&str>, kept: Vec<&str>) {
+        for remove in removes {
+            let mut trie = &mut *self;
+            for (end, next) in prefix_ends(remove) {
+                match kept_depth(&kept, &remove[..end]) {
+                    Some(depth) => { trie.id_to_depth.insert(id, depth); },
+                    None => { trie.id_to_depth.remove(&id); },
+                }
+                let child = match next {
+                    Some(c) => trie.children.get_mut(&c),
+                    None => None,
+                };
+                match child {
+                    Some(child) => trie = child,
+                    None => break,
+                }
+            }
+        }
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage::default();
+        let mut tries_to_visit = vec![self];
+        while let Some(trie) = tries_to_visit.pop() {
+            usage.node_count += 1;
+            usage.nodes += size_of::<Self>();
+            usage.maps += children_map_bytes(&trie.children);
+            usage.id_sets += memory::hash_map_bytes(&trie.id_to_depth);
+            tries_to_visit.extend(trie.children.values());
+        }
+        usage
+    }
+    fn estimate(&self, search: &str) -> u64 {
+        estimate_ids(self, search)
+    }
+    fn search_ids<'a>(&'a self, search: &'a str, filter: Option<&'a FxHashSet<u64>>) -> Box<dyn Iterator<Item = u64> + 'a> {
+        Box::new(NodeIds::new(self, search, filter))
+    }
 }
 impl Default for Trie4 {
     fn default() -> Self {
         Self::new()
     }
 }
+impl Node for Trie4 {
+    fn child(&self, c: char) -> Option<&Self> {
+        self.children.get(&c)
+    }
+    fn child_mut(&mut self, c: char) -> Option<&mut Self> {
+        self.children.get_mut(&c)
+    }
+    fn id_count(&self) -> u64 {
+        self.id_to_depth.len() as u64
+    }
+    fn remove_id(&mut self, id: u64) {
+        self.id_to_depth.remove(&id);
+    }
+    fn children(&self) -> &FxHashMap<char, Self> {
+        &self.children
+    }
+    fn ids(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        Box::new(self.id_to_depth.keys().copied())
+    }
+    fn has_id(&self, id: u64) -> bool {
+        self.id_to_depth.contains_key(&id)
+    }
+}
 
 //non-recursive, search-match pruning
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -142,14 +480,16 @@ impl Trie3 {
         let mut trie = self;
         trie.ids.insert(id);
         for c in insert.chars() {
-            trie = trie.children.entry(c).or_insert(Trie3::new());
+            trie = trie.children.entry(c).or_default();
             trie.ids.insert(id);
         }
     }
     fn search_single(&self, search: &str, filter: Option<&FxHashSet<u64>>) -> FxHashSet<u64> {
         let mut results = FxHashSet::default();
         let mut tries_to_visit = vec![(self, search)];
+        let mut visited = 0;
         'trie: while let Some((trie, search)) = tries_to_visit.pop() {
+            visited += 1;
             if let Some(f) = filter { 
                 let mut keep_searching = false;
                 for id in f { //if this trie contains an index in the filter, keep searching, otherwise skip this branch
@@ -162,18 +502,17 @@ impl Trie3 {
                     continue 'trie
                 }
             }
-            if let Some(first_char) = search.chars().nth(0) {
-                for c in CHARS.iter() {
-                    if let Some(new_trie) = trie.children.get(c) {
-                        let new_search = if *c == first_char { &search[1..] } else { search };
-                        tries_to_visit.push((new_trie, new_search));
-                    }
+            if let Some(first_char) = search.chars().next() {
+                for (c, new_trie) in trie.children.iter() {
+                    let new_search = if *c == first_char { &search[first_char.len_utf8()..] } else { search };
+                    tries_to_visit.push((new_trie, new_search));
                 }
             }
             else {
                 results = results.union(&trie.ids).cloned().collect();
             }
         }
+        profile::count_nodes_visited(visited);
         results
     }
 }
@@ -206,12 +545,56 @@ impl Trie for Trie3 {
             }
         }
     }
+    fn remove(&mut self, id: u64, removes: Vec<&str>, kept: Vec<&str>) {
+        remove_ids(self, id, removes, kept)
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage::default();
+        let mut tries_to_visit = vec![self];
+        while let Some(trie) = tries_to_visit.pop() {
+            usage.node_count += 1;
+            usage.nodes += size_of::<Self>();
+            usage.maps += children_map_bytes(&trie.children);
+            usage.id_sets += memory::hash_set_bytes(&trie.ids);
+            tries_to_visit.extend(trie.children.values());
+        }
+        usage
+    }
+    fn estimate(&self, search: &str) -> u64 {
+        estimate_ids(self, search)
+    }
+    fn search_ids<'a>(&'a self, search: &'a str, filter: Option<&'a FxHashSet<u64>>) -> Box<dyn Iterator<Item = u64> + 'a> {
+        Box::new(NodeIds::new(self, search, filter))
+    }
 }
 impl Default for Trie3 {
     fn default() -> Self {
         Self::new()
     }
 }
+impl Node for Trie3 {
+    fn child(&self, c: char) -> Option<&Self> {
+        self.children.get(&c)
+    }
+    fn child_mut(&mut self, c: char) -> Option<&mut Self> {
+        self.children.get_mut(&c)
+    }
+    fn id_count(&self) -> u64 {
+        self.ids.len() as u64
+    }
+    fn remove_id(&mut self, id: u64) {
+        self.ids.remove(&id);
+    }
+    fn children(&self) -> &FxHashMap<char, Self> {
+        &self.children
+    }
+    fn ids(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        Box::new(self.ids.iter().copied())
+    }
+    fn has_id(&self, id: u64) -> bool {
+        self.ids.contains(&id)
+    }
+}
 
 //non-recursive, no tree pruning
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -230,26 +613,27 @@ impl Trie2 {
         let mut trie = self;
         trie.ids.insert(id);
         for c in insert.chars() {
-            trie = trie.children.entry(c).or_insert(Trie2::new());
+            trie = trie.children.entry(c).or_default();
             trie.ids.insert(id);
         }
     }
     fn search_single(&self, search: &str) -> FxHashSet<u64> {
         let mut results = FxHashSet::default();
         let mut tries_to_visit = vec![(self, search)];
+        let mut visited = 0;
         while let Some((trie, search)) = tries_to_visit.pop() {
-            if let Some(first_char) = search.chars().nth(0) {
-                for c in CHARS.iter() {
-                    if let Some(new_trie) = trie.children.get(c) {
-                        let new_search = if *c == first_char { &search[1..] } else { search };
-                        tries_to_visit.push((new_trie, new_search));
-                    }
+            visited += 1;
+            if let Some(first_char) = search.chars().next() {
+                for (c, new_trie) in trie.children.iter() {
+                    let new_search = if *c == first_char { &search[first_char.len_utf8()..] } else { search };
+                    tries_to_visit.push((new_trie, new_search));
                 }
             }
             else {
                 results = results.union(&trie.ids).cloned().collect();
             }
         }
+        profile::count_nodes_visited(visited);
         results
     }
 }
@@ -276,12 +660,56 @@ impl Trie for Trie2 {
             }
         }
     }
+    fn remove(&mut self, id: u64, removes: Vec<&str>, kept: Vec<&str>) {
+        remove_ids(self, id, removes, kept)
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage::default();
+        let mut tries_to_visit = vec![self];
+        while let Some(trie) = tries_to_visit.pop() {
+            usage.node_count += 1;
+            usage.nodes += size_of::<Self>();
+            usage.maps += children_map_bytes(&trie.children);
+            usage.id_sets += memory::hash_set_bytes(&trie.ids);
+            tries_to_visit.extend(trie.children.values());
+        }
+        usage
+    }
+    fn estimate(&self, search: &str) -> u64 {
+        estimate_ids(self, search)
+    }
+    fn search_ids<'a>(&'a self, search: &'a str, _filter: Option<&'a FxHashSet<u64>>) -> Box<dyn Iterator<Item = u64> + 'a> {
+        Box::new(NodeIds::new(self, search, None))
+    }
 }
 impl Default for Trie2 {
     fn default() -> Self {
         Self::new()
     }
 }
+impl Node for Trie2 {
+    fn child(&self, c: char) -> Option<&Self> {
+        self.children.get(&c)
+    }
+    fn child_mut(&mut self, c: char) -> Option<&mut Self> {
+        self.children.get_mut(&c)
+    }
+    fn id_count(&self) -> u64 {
+        self.ids.len() as u64
+    }
+    fn remove_id(&mut self, id: u64) {
+        self.ids.remove(&id);
+    }
+    fn children(&self) -> &FxHashMap<char, Self> {
+        &self.children
+    }
+    fn ids(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        Box::new(self.ids.iter().copied())
+    }
+    fn has_id(&self, id: u64) -> bool {
+        self.ids.contains(&id)
+    }
+}
 
 //recursive, no tree pruning
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -298,19 +726,18 @@ impl Trie1 {
     }
     fn add_rec(trie: &mut Trie1, id: u64, insert: &str) {
         trie.ids.insert(id);
-        if let Some(first_char) = insert.chars().nth(0) {
-            let trie = trie.children.entry(first_char).or_insert(Trie1::new());
-            Self::add_rec(trie, id, &insert[1..]);
+        if let Some(first_char) = insert.chars().next() {
+            let trie = trie.children.entry(first_char).or_default();
+            Self::add_rec(trie, id, &insert[first_char.len_utf8()..]);
         }
     }
     fn search_rec(trie: &Trie1, search: &str) -> FxHashSet<u64> {
-        if let Some(first_char) = search.chars().nth(0) {
+        profile::count_nodes_visited(1);
+        if let Some(first_char) = search.chars().next() {
             let mut results = FxHashSet::default();
-            for c in CHARS.iter() {
-                if let Some(trie) = trie.children.get(c) {
-                    let new_search = if *c == first_char { &search[1..] } else { search };
-                    results = results.union(&Self::search_rec(trie, new_search)).cloned().collect();
-                }
+            for (c, trie) in trie.children.iter() {
+                let new_search = if *c == first_char { &search[first_char.len_utf8()..] } else { search };
+                results = results.union(&Self::search_rec(trie, new_search)).cloned().collect();
             }
             results
         }
@@ -342,86 +769,53 @@ impl Trie for Trie1 {
     fn delete(&mut self, id: u64) {
         Self::delete_rec(self, id)
     }
+    fn remove(&mut self, id: u64, removes: Vec<&str>, kept: Vec<&str>) {
+        remove_ids(self, id, removes, kept)
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage::default();
+        let mut tries_to_visit = vec![self];
+        while let Some(trie) = tries_to_visit.pop() {
+            usage.node_count += 1;
+            usage.nodes += size_of::<Self>();
+            usage.maps += children_map_bytes(&trie.children);
+            usage.id_sets += memory::hash_set_bytes(&trie.ids);
+            tries_to_visit.extend(trie.children.values());
+        }
+        usage
+    }
+    fn estimate(&self, search: &str) -> u64 {
+        estimate_ids(self, search)
+    }
+    fn search_ids<'a>(&'a self, search: &'a str, _filter: Option<&'a FxHashSet<u64>>) -> Box<dyn Iterator<Item = u64> + 'a> {
+        Box::new(NodeIds::new(self, search, None))
+    }
 }
 impl Default for Trie1 {
     fn default() -> Self {
         Self::new()
     }
 }
-
-//non-recursive, search-match pruning and depth pruning
-// #[derive(Debug, Clone, PartialEq, Eq)]
-// pub struct Trie5 {
-//     children: FxHashMap<char, Trie4>,
-    
-//     first: FxHashMap<char, FxHashMap<usize, *mut Trie4>>,
-//     last: FxHashMap<char, FxHashMap<usize, *mut Trie4>>,
-//     next: *mut Trie4,
-//     //prev: *mut Trie4,
-//     //parent: *mut Trie4,
-
-//     id_to_depth: FxHashMap<u64, usize>,
-//     //max_depth: usize,
-//     //depth: usize,
-//     //position: usize,
-//     //value: char,
-// }
-// impl Trie5 {
-//     fn new(max_depth: usize, id_reserve: usize) -> Self {
-//         Trie5 {
-//             children: HashMap::with_capacity_and_hasher(CHARS.len(), BuildHasherDefault::<FxHasher>::default()),
-
-//             first: HashMap::with_capacity_and_hasher(CHARS.len(), BuildHasherDefault::<FxHasher>::default()),
-//             last: HashMap::with_capacity_and_hasher(CHARS.len(), BuildHasherDefault::<FxHasher>::default()),
-//             next: ptr::null_mut(),
-
-//             id_to_depth: HashMap::with_capacity_and_hasher(id_reserve, BuildHasherDefault::<FxHasher>::default()),
-//         }
-//     }
-//     fn search_single(&self, search: &str, filter: Option<&FxHashSet<u64>>) -> FxHashSet<u64> {
-//         let mut results = FxHashSet::default();
-//         let mut tries_to_visit = vec![(self, search)];
-//         while let Some((trie, search)) = tries_to_visit.pop() {
-//             if let Some(first_char) = search.chars().nth(0) {
-
-//                 // for c in CHARS.iter() {
-//                 //     if let Some(new_trie) = trie.children.get(c) {
-//                 //         let new_search = if *c == first_char { &search[1..] } else { search };
-//                 //         tries_to_visit.push((new_trie, new_search));
-//                 //     }
-//                 // }
-//             }
-//             else {
-//                 let ids = &trie.id_to_depth.keys().cloned().collect();
-//                 results = results.union(ids).cloned().collect();
-//             }
-//         }
-//         results
-//     }
-// }
-// impl Trie for Trie5 {
-//     fn add(&mut self, id: u64, inserts: Vec<&str>) {
-        
-//     }
-//     fn search(&self, searches: Vec<&str>, filter: Option<&FxHashSet<u64>>) -> FxHashSet<u64> {
-//         let mut searches = searches.iter();
-//         if let Some(first_search) = searches.next() {
-//             let mut result = Self::search_single(self, first_search, filter);
-//             for search in searches { //use results of previous searches to filter ids in subsequent searches
-//                 result = result.intersection(&Self::search_single(self, search, Some(&result))).cloned().collect();
-//             }
-//             result
-//         }
-//         else {
-//             FxHashSet::default()
-//         }
-//     }
-//     fn delete(&mut self, id: u64) {
-        
-//     }
-// }
-// impl Default for Trie5 {
-//     fn default() -> Self {
-//         Self::new(32, 32)
-//     }
-// }
\ No newline at end of file
+impl Node for Trie1 {
+    fn child(&self, c: char) -> Option<&Self> {
+        self.children.get(&c)
+    }
+    fn child_mut(&mut self, c: char) -> Option<&mut Self> {
+        self.children.get_mut(&c)
+    }
+    fn id_count(&self) -> u64 {
+        self.ids.len() as u64
+    }
+    fn remove_id(&mut self, id: u64) {
+        self.ids.remove(&id);
+    }
+    fn children(&self) -> &FxHashMap<char, Self> {
+        &self.children
+    }
+    fn ids(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        Box::new(self.ids.iter().copied())
+    }
+    fn has_id(&self, id: u64) -> bool {
+        self.ids.contains(&id)
+    }
+}
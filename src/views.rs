@@ -0,0 +1,70 @@
+//Saved searches: named search params which "view <name>" runs again, and which other searches can take in as @<name>
+//They're kept next to the list they search rather than in it, by whoever runs queries against the list, and expanded before the list sees a search
+//Any @names in a search are resolved as it's saved, so views never refer to each other
+
+use crate::*;
+use crate::memory::{self, MemoryUsage};
+
+use std::collections::BTreeMap;
+
+//views are found by their normalised names, so @Mine and @mine are the same view
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Views {
+    saved: BTreeMap<String, SearchParams>,
+}
+impl Views {
+    pub fn new() -> Self {
+        Views {
+            saved: BTreeMap::new(),
+        }
+    }
+
+    //saves a search under a name, replacing whatever was saved under it before
+    pub fn save(&mut self, name: &str, sp: SearchParams) -> Result<(), QueryError> {
+        let sp = self.expand(&sp)?;
+        self.saved.insert(normalise(name), sp);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SearchParams> {
+        self.saved.get(&normalise(name))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.saved.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.saved.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.saved.is_empty()
+    }
+
+    //a search with the terms of every view it names added to its own, and each of its options that it leaves out
+    //taken from the first view giving one, so "search @mine offset:10" pages through what "view mine" lists
+    pub fn expand(&self, sp: &SearchParams) -> Result<SearchParams, QueryError> {
+        let mut expanded = SearchParams{views: Vec::new(), ..sp.clone()};
+        for name in &sp.views {
+            let view = self.get(name).ok_or_else(|| QueryError(format!("no search is saved as @{}", name)))?;
+            expanded.params.extend(view.params.iter().cloned());
+            let options = &mut expanded.options;
+            options.limit = options.limit.or(view.options.limit);
+            options.offset = options.offset.or(view.options.offset);
+            options.sort = options.sort.or(view.options.sort);
+        }
+        Ok(expanded)
+    }
+
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            other: memory::btree_map_bytes(&self.saved) + self.saved.iter().map(|(name, sp)| {
+                name.capacity() + memory::vec_bytes(&sp.params) + sp.params.iter().map(|param| match param {
+                    SearchWordOrTag::RawWord(s) | SearchWordOrTag::RawTag(s) => s.capacity(),
+                }).sum::<usize>()
+            }).sum::<usize>(),
+            ..MemoryUsage::default()
+        }
+    }
+}
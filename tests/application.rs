@@ -0,0 +1,100 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const QUERIES: &str = "4\nadd \"buy milk\" #shopping\nadd \"call mum\"\nsearch mu\ndone 0\n";
+
+fn application() -> Command {
+    Command::cargo_bin("application").unwrap()
+}
+
+//a path of its own for each test, as tests run side by side
+fn scratch(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("todo_swamp_application_{}_{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&path);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn every_backend_answers_the_same() {
+    for backend in &["naive", "naive2", "trie1", "trie2", "trie3", "trie4", "trie5", "sharded"] {
+        application().args(["--backend", backend]).write_stdin(QUERIES).assert()
+            .success()
+            .stdout("0\n1\n1 item(s) found\n1 \"call mum\" \ndone\n");
+    }
+    application().args(["--backend", "trie6"]).assert().failure().stderr(predicate::str::contains("invalid value 'trie6'"));
+}
+
+#[test]
+fn shards_are_only_taken_by_the_sharded_backend() {
+    application().args(["--backend", "sharded", "--shards", "3"]).write_stdin(QUERIES).assert()
+        .success()
+        .stdout("0\n1\n1 item(s) found\n1 \"call mum\" \ndone\n");
+    application().args(["--shards", "3"]).assert().failure().code(2).stderr(predicate::str::contains("--shards can only be used with --backend sharded"));
+    application().args(["--backend", "sharded", "--shards", "0"]).assert().failure().code(2);
+}
+
+#[test]
+fn queries_are_read_from_and_written_to_files() {
+    let (input, output) = (scratch("in"), scratch("out"));
+    fs::write(&input, QUERIES).unwrap();
+    application().arg("-i").arg(&input).arg("-o").arg(&output).assert().success().stdout("");
+    assert_eq!(fs::read_to_string(&output).unwrap(), "0\n1\n1 item(s) found\n1 \"call mum\" \ndone\n");
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&output).unwrap();
+
+    application().arg("-i").arg(scratch("missing")).assert().failure();
+}
+
+#[test]
+fn the_count_line_can_be_left_out() {
+    //without --no-count the first query is taken for the count
+    application().arg("--no-count").write_stdin("add \"buy milk\"\nsearch milk\n").assert()
+        .success()
+        .stdout("0\n1 item(s) found\n0 \"buy milk\" \n");
+    application().write_stdin("add \"buy milk\"\nsearch milk\n").assert().success().stdout("0 item(s) found\n");
+    application().args(["--no-count", "-n", "1"]).write_stdin("add \"buy milk\"\nsearch milk\n").assert().success().stdout("0\n");
+}
+
+#[test]
+fn timed_and_bench_runs_report_what_they_answered() {
+    application().args(["--timed", "10000"]).write_stdin(QUERIES).assert()
+        .success()
+        .stdout(predicate::str::starts_with("0\n1\n"))
+        .stderr(predicate::str::contains("4 queries answered in under 10000ms"));
+    application().arg("--bench").write_stdin(QUERIES).assert()
+        .success()
+        .stdout("")
+        .stderr(predicate::str::contains("4 queries answered in"));
+}
+
+#[test]
+fn correctness_runs_write_every_backend_sorted() {
+    let dir = scratch("correctness");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("small.in"), QUERIES).unwrap();
+    application().arg("--correctness").arg("small").arg("--tests-dir").arg(&dir).assert().success();
+    let naive = fs::read_to_string(dir.join("correct/small_naive.out")).unwrap();
+    assert_eq!(naive, "0\n1\n1 item(s) found\n1 \"call mum\" \ndone\n");
+    for backend in &["naive2", "trie1", "trie2", "trie3", "trie4", "trie5", "sharded"] {
+        assert_eq!(fs::read_to_string(dir.join(format!("correct/small_{}.out", backend))).unwrap(), naive, "{}", backend);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_modes_which_cannot_go_together_are_refused() {
+    for args in &[
+        vec!["--interactive", "-i", "queries.in"],
+        vec!["--interactive", "--timed", "10"],
+        vec!["--bench", "-o", "results.out"],
+        vec!["--correctness", "small", "-i", "queries.in"],
+        vec!["--correctness", "small", "--bench"],
+    ] {
+        application().args(args).assert().failure().code(2).stderr(predicate::str::contains("cannot be used with"));
+    }
+}
@@ -0,0 +1,113 @@
+use todo_swamp::*;
+use todo_swamp::cache::CachedTodoList;
+
+fn add<T: TodoLister>(tl: &mut T, description: &str, tags: &[&str]) -> Index {
+    tl.push(description.split(' ').map(Word::new).collect(), tags.iter().map(|t| Tag::new(t)).collect()).index
+}
+
+fn search(terms: &str) -> SearchParams {
+    parser::search_terms(terms).unwrap().1
+}
+
+fn found<T: TodoLister>(tl: &T, terms: &str) -> Vec<u64> {
+    tl.search(search(terms)).iter().map(|item| item.index.value()).collect()
+}
+
+fn filled() -> CachedTodoList<TodoList> {
+    let mut tl = CachedTodoList::new(TodoList::new());
+    add(&mut tl, "buy milk", &["shopping"]);
+    add(&mut tl, "call mum", &["home"]);
+    add(&mut tl, "fix bike", &["home", "urgent"]);
+    tl
+}
+
+#[test]
+fn repeated_searches_are_hits() {
+    let tl = filled();
+    assert_eq!(found(&tl, "#home"), [1, 2]);
+    assert_eq!(found(&tl, "#home"), [1, 2]);
+    //the same terms, differently cased, ordered or repeated, are the same search
+    assert_eq!(found(&tl, "#HOME #home"), [1, 2]);
+    let stats = tl.cache_stats();
+    assert_eq!((stats.hits(), stats.misses()), (2, 1));
+    assert_eq!(tl.cached(), 1);
+}
+
+#[test]
+fn pushes_only_drop_searches_their_item_matches() {
+    let mut tl = filled();
+    found(&tl, "#home");
+    found(&tl, "milk");
+    add(&mut tl, "walk dog", &["home"]);
+    assert_eq!(tl.cached(), 1);
+    assert_eq!(found(&tl, "milk"), [0]);
+    assert_eq!(found(&tl, "#home"), [1, 2, 3]);
+    let stats = tl.cache_stats();
+    assert_eq!((stats.hits(), stats.misses()), (1, 3));
+}
+
+#[test]
+fn dones_only_drop_searches_which_found_the_item() {
+    let mut tl = filled();
+    found(&tl, "#home");
+    found(&tl, "milk");
+    assert_eq!(tl.done_with_index(Index::new(2)), Some(Index::new(2)));
+    assert_eq!(tl.cached(), 1);
+    assert_eq!(tl.done_with_index(Index::new(9)), None);
+    assert_eq!(tl.cached(), 1);
+    assert_eq!(found(&tl, "#home"), [1]);
+    assert_eq!(found(&tl, "milk"), [0]);
+    let stats = tl.cache_stats();
+    assert_eq!((stats.hits(), stats.misses()), (1, 3));
+}
+
+#[test]
+fn full_caches_make_room() {
+    let mut tl = CachedTodoList::with_capacity(TodoList::new(), 2);
+    add(&mut tl, "buy milk", &[]);
+    for terms in &["b", "m", "k", "b"] {
+        assert_eq!(found(&tl, terms), [0]);
+        assert!(tl.cached() <= 2);
+    }
+    let uncached = CachedTodoList::with_capacity(filled().into_inner(), 0);
+    assert_eq!(found(&uncached, "mum"), [1]);
+    assert_eq!(found(&uncached, "mum"), [1]);
+    assert_eq!((uncached.cache_stats().misses(), uncached.cached()), (2, 0));
+}
+
+//a search used again and again stays remembered however many others come and go
+#[test]
+fn searches_used_longest_ago_make_room() {
+    let tl = CachedTodoList::with_capacity(filled().into_inner(), 3);
+    for terms in &["mum", "b", "c", "mum", "i", "k", "mum", "l", "u", "mum"] {
+        found(&tl, terms);
+    }
+    assert_eq!(tl.cached(), 3);
+    let stats = tl.cache_stats();
+    assert_eq!((stats.hits(), stats.misses()), (3, 7));
+    //of the others, only the latest two are left
+    assert_eq!(found(&tl, "u"), found(&tl, "l"));
+    assert_eq!((stats.hits(), stats.misses()), (5, 7));
+    //counts answered from an entry use it too
+    assert_eq!(tl.count(search("mum")), 1);
+    found(&tl, "b");
+    assert_eq!(found(&tl, "mum"), [1]);
+    assert_eq!((stats.hits(), stats.misses()), (7, 8));
+}
+
+#[test]
+fn tag_edits_drop_searches_the_item_was_or_is_in() {
+    let mut tl = filled();
+    found(&tl, "#home");
+    found(&tl, "#urgent");
+    found(&tl, "milk");
+    let edit = TagEdit{add: vec![Tag::new("urgent")], remove: vec![Tag::new("home")]};
+    assert_eq!(tl.edit_tags(Index::new(1), &edit), Some(true));
+    assert_eq!(tl.cached(), 1);
+    assert_eq!(tl.edit_tags(Index::new(1), &edit), Some(false));
+    assert_eq!(tl.edit_tags(Index::new(9), &edit), None);
+    assert_eq!(tl.cached(), 1);
+    assert_eq!(found(&tl, "#home"), [2]);
+    assert_eq!(found(&tl, "#urgent"), [1, 2]);
+    assert_eq!(found(&tl, "milk"), [0]);
+}
@@ -0,0 +1,65 @@
+use todo_swamp::*;
+use todo_swamp::cache::CachedTodoList;
+use todo_swamp::complete;
+use todo_swamp::sharded::ShardedTodoList;
+use todo_swamp::views::Views;
+
+use std::collections::BTreeMap;
+
+fn filled<T: TodoLister>(mut tl: T) -> T {
+    let items: [(&str, &[&str]); 5] = [
+        ("buy milk", &["shopping", "home"]),
+        ("buy bread", &["Shopping", "shopping"]),
+        ("call mum", &["home"]),
+        ("fix bike", &["home", "hobby"]),
+        ("build shed", &["hobby", "urgent"]),
+    ];
+    for (description, tags) in &items {
+        tl.push(description.split(' ').map(Word::new).collect(), tags.iter().map(|t| Tag::new(t)).collect());
+    }
+    tl.done_with_index(Index::new(4));
+    tl
+}
+
+fn tags(pairs: &[(&str, usize)]) -> BTreeMap<String, usize> {
+    pairs.iter().map(|(tag, open)| (tag.to_string(), *open)).collect()
+}
+
+fn check_tags<T: TodoLister>(tl: &T) {
+    assert_eq!(tl.tags(""), tags(&[("hobby", 1), ("home", 3), ("shopping", 2), ("urgent", 0)]));
+    assert_eq!(tl.tags("ho"), tags(&[("hobby", 1), ("home", 3)]));
+    assert_eq!(tl.tags("hom"), tags(&[("home", 3)]));
+    assert_eq!(tl.tags("homes"), tags(&[]));
+    assert_eq!(tl.tags("z"), tags(&[]));
+}
+
+#[test]
+fn every_backend_lists_tags() {
+    check_tags(&filled(TodoList::new()));
+    check_tags(&filled(TodoList2::new()));
+    check_tags(&filled(TriedoList::<Trie2>::new()));
+    check_tags(&filled(TriedoList::<Trie5>::new()));
+    check_tags(&filled(ShardedTodoList::with_shards(2)));
+    check_tags(&filled(CachedTodoList::new(TodoList::new())));
+}
+
+#[test]
+fn tags_queries_normalise_their_prefix() {
+    let mut tl = filled(TriedoList::<Trie3>::new());
+    let answer = runner::try_run_line("tags #SHOP", &mut tl, &mut Views::new()).unwrap();
+    assert_eq!(answer, QueryResult::Tags(tags(&[("shopping", 2)])));
+    assert_eq!(answer.to_string(), "1 tag(s) found\n#shopping 2 open");
+}
+
+//most used first, then alphabetically, leaving out tags with no open items and words only on done items
+#[test]
+fn suggestions_come_from_open_items() {
+    let tl = filled(TriedoList::<Trie4>::new());
+    assert_eq!(complete::suggestions(&tl, "#h", 10), ["#home", "#hobby"]);
+    assert_eq!(complete::suggestions(&tl, "#u", 10), Vec::<String>::new());
+    assert_eq!(complete::suggestions(&tl, "#", 2), ["#home", "#shopping"]);
+    assert_eq!(complete::suggestions(&tl, "bu", 10), ["buy"]);
+    assert_eq!(complete::suggestions(&tl, "B", 10), ["buy", "bike", "bread"]);
+    assert_eq!(complete::suggestions(&tl, "b", 1), ["buy"]);
+    assert_eq!(complete::suggestions(&tl, "", 10), Vec::<String>::new());
+}
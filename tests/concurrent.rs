@@ -0,0 +1,66 @@
+use todo_swamp::*;
+use todo_swamp::concurrent::ConcurrentTodoList;
+
+use std::sync::Arc;
+use std::thread;
+
+fn words(s: &str) -> Vec<Word> {
+    s.split(' ').map(Word::new).collect()
+}
+
+fn search(terms: &str) -> SearchParams {
+    parser::search_terms(terms).unwrap().1
+}
+
+//searchers run alongside a writer, every search has to see the list as it was between two whole pushes or dones
+fn parallel_searches_see_whole_changes<T: TodoLister + Send + Sync + 'static>(tl: T) {
+    let tl = Arc::new(ConcurrentTodoList::new(tl));
+    let writer = {
+        let tl = Arc::clone(&tl);
+        thread::spawn(move || {
+            for i in 0..200 {
+                let item = tl.push(words("water the plants"), Tag::from_strings(vec!["home"]));
+                if i % 2 == 1 {
+                    tl.done_with_index(item.index).unwrap();
+                }
+            }
+        })
+    };
+    let searchers: Vec<_> = (0..4).map(|_| {
+        let tl = Arc::clone(&tl);
+        thread::spawn(move || {
+            for _ in 0..200 {
+                //odd items are done straight after being pushed, so at most the latest of them can be seen open
+                let found = tl.search(search("plants #home"));
+                assert!(found.iter().all(|item| !item.done));
+                assert!(found.iter().filter(|item| item.index.value() % 2 == 1).count() <= 1);
+            }
+        })
+    }).collect();
+    writer.join().unwrap();
+    for searcher in searchers {
+        searcher.join().unwrap();
+    }
+    assert_eq!(tl.search(search("plants #home")).len(), 100);
+    assert_eq!(tl.run_line("search water"), Ok(QueryResult::Found(tl.search(search("water")))));
+}
+
+#[test]
+fn naive() {
+    parallel_searches_see_whole_changes(TodoList::new());
+}
+
+#[test]
+fn naive2() {
+    parallel_searches_see_whole_changes(TodoList2::new());
+}
+
+#[test]
+fn trie3() {
+    parallel_searches_see_whole_changes(TriedoList::<Trie3>::new());
+}
+
+#[test]
+fn trie5() {
+    parallel_searches_see_whole_changes(TriedoList::<Trie5>::new());
+}
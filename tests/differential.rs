@@ -0,0 +1,144 @@
+use todo_swamp::differential::Differential;
+use todo_swamp::generator::{Generator, GeneratorConfig};
+
+//a small vocabulary of short words, so most searches find something and items are often done twice
+fn queries(seed: u64, count: usize) -> Vec<String> {
+    let config = GeneratorConfig {
+        add_weight: 3,
+        done_weight: 2,
+        search_weight: 5,
+        vocabulary: 30,
+        tag_vocabulary: 6,
+        word_length_weights: vec![1, 2, 3, 3, 2],
+        search_option_chance: 0.3,
+        seed,
+        ..GeneratorConfig::new()
+    };
+    Generator::new(config).take(count).collect()
+}
+
+//runs each seed's queries through the backends, each followed by whatever more queries the test asks for after it
+//(tests of one feature take fewer seeds, as they run the plain queries over again)
+fn check_random<F: Fn(usize, &str) -> Vec<String>>(seeds: u64, also: F) {
+    let (seeds, count) = if cfg!(miri) { (1, 50) } else { (seeds, 1000) }; //miri runs far slower
+    for seed in 0..seeds {
+        let mut differential = Differential::new();
+        for (i, q) in queries(seed, count).iter().enumerate() {
+            match differential.run_line(q) {
+                Ok(Ok(_)) => (),
+                Ok(Err(e)) => assert!(q.starts_with("done"), "seed {}: {}", seed, e),
+                Err(mismatch) => panic!("seed {}: {}", seed, mismatch),
+            }
+            for also in also(i, q) {
+                if let Err(mismatch) = differential.run_line(&also) {
+                    panic!("seed {}: {}", seed, mismatch);
+                }
+            }
+        }
+    }
+}
+
+fn search_terms(q: &str) -> Option<&str> {
+    q.strip_prefix("search ")
+}
+
+#[test]
+fn backends_agree_on_random_queries() {
+    check_random(10, |_, _| Vec::new());
+}
+
+#[test]
+fn backends_agree_on_counts() {
+    check_random(3, |_, q| search_terms(q).map(|terms| format!("count {}", terms)).into_iter().collect());
+}
+
+#[test]
+fn backends_agree_on_stats() {
+    check_random(3, |i, _| if i % 10 == 0 { vec![String::from("stats")] } else { Vec::new() });
+}
+
+#[test]
+fn backends_agree_on_tags() {
+    check_random(3, |i, _| match i % 20 {
+        0 => vec![String::from("tags")],
+        10 => vec![String::from("tags a")],
+        _ => Vec::new(),
+    });
+}
+
+//tags added and removed by the items each search finds, and every so often those items done
+#[test]
+fn backends_agree_on_bulk_queries() {
+    check_random(3, |i, q| match (search_terms(q), i % 3) {
+        (Some(terms), 0) => vec![format!("tag add #bulk #b{} where {}", i % 5, terms), String::from("search #bulk")],
+        (Some(terms), 1) => vec![format!("tag remove #bulk where {}", terms), String::from("count #bul")],
+        (Some(terms), _) if i % 30 == 2 => vec![format!("done where {}", terms)],
+        _ => Vec::new(),
+    });
+}
+
+//edits to items both open and done, by halving the index which was just done
+#[test]
+fn backends_agree_on_tag_edits() {
+    check_random(3, |i, q| match q.strip_prefix("done ").and_then(|n| n.parse::<u64>().ok()) {
+        Some(n) => vec![
+            if i % 3 == 0 { format!("tag {} +#edit", n / 2) } else { format!("tag {} -#edit +#e{}", n / 2, i % 5) },
+            format!("tag {} -#e{}", n, i % 5),
+            String::from("search #e"),
+        ],
+        None => Vec::new(),
+    });
+}
+
+//searches saved over and over under a few names, and run alone and together
+#[test]
+fn backends_agree_on_views() {
+    check_random(3, |i, q| match search_terms(q) {
+        Some(terms) => vec![
+            format!("save s{} {}", i % 3, terms),
+            format!("view s{}", i % 3),
+            format!("search @s{} a", (i + 1) % 3),
+            format!("count @s{} @s{} limit:3", i % 3, (i + 2) % 3),
+        ],
+        None => Vec::new(),
+    });
+}
+
+#[test]
+fn done_items_are_not_found_by_tag() {
+    let mut differential = Differential::new();
+    for q in &["add \"buy milk\" #shopping", "add \"call mum\" #home", "done 0"] {
+        assert!(differential.run_line(q).unwrap().is_ok());
+    }
+    assert_eq!(differential.run_line("search #shop").unwrap().unwrap().to_string(), "0 item(s) found");
+}
+
+#[test]
+fn counts_and_stats_follow_dones() {
+    let mut differential = Differential::new();
+    for q in &["add \"buy milk\" #shopping #Shopping", "add \"call mum\" #home", "add \"fix bike\" #home #urgent", "done 1", "done 1"] {
+        assert!(differential.run_line(q).unwrap().is_ok());
+    }
+    let mut answer = |q| differential.run_line(q).unwrap().unwrap().to_string();
+    assert_eq!(answer("count #urg"), "1");
+    assert_eq!(answer("count i sort:score offset:1"), "1");
+    assert_eq!(answer("stats"), "2 open, 1 done\n#home 1 open, 1 done\n#shopping 1 open, 0 done\n#urgent 1 open, 0 done");
+}
+
+#[test]
+fn bulk_queries_change_every_match() {
+    let mut differential = Differential::new();
+    for q in &["add \"buy milk\" #shopping", "add \"buy bread\" #shopping", "add \"fix bike\" #home", "done 1"] {
+        assert!(differential.run_line(q).unwrap().is_ok());
+    }
+    let mut answer = |q| differential.run_line(q).unwrap().unwrap().to_string();
+    assert_eq!(answer("tag add #Urgent #home where buy"), "1 item(s) affected");
+    assert_eq!(answer("tag add #urgent where i"), "1 item(s) affected"); //"buy milk" already has it
+    assert_eq!(answer("search #urg"), "2 item(s) found\n0 \"buy milk\" #shopping #Urgent #home\n2 \"fix bike\" #home #urgent");
+    assert_eq!(answer("tag remove #shopping where #home"), "1 item(s) affected");
+    assert_eq!(answer("stats"), "2 open, 1 done\n#home 2 open, 0 done\n#shopping 0 open, 1 done\n#urgent 2 open, 0 done");
+    assert_eq!(answer("done where #urgent limit:1 sort:-index"), "1 item(s) affected");
+    assert_eq!(answer("done where #urgent"), "1 item(s) affected");
+    assert_eq!(answer("done where #urgent"), "0 item(s) affected");
+    assert_eq!(answer("stats"), "0 open, 3 done\n#home 0 open, 2 done\n#shopping 0 open, 1 done\n#urgent 0 open, 2 done");
+}
@@ -0,0 +1,46 @@
+use todo_swamp::*;
+use todo_swamp::generator::{self, Generator, GeneratorConfig};
+
+fn config(seed: u64) -> GeneratorConfig {
+    GeneratorConfig {
+        search_option_chance: 0.3,
+        seed,
+        ..GeneratorConfig::new()
+    }
+}
+
+fn lines(config: GeneratorConfig, count: usize) -> Vec<String> {
+    Generator::new(config).take(count).collect()
+}
+
+//benchmark inputs are written again from their seed wherever they're needed, rather than kept in the repo
+#[test]
+fn the_same_seed_and_config_make_the_same_queries() {
+    assert_eq!(lines(config(1), 2000), lines(config(1), 2000));
+    assert_ne!(lines(config(1), 2000), lines(config(2), 2000));
+    assert_ne!(lines(config(1), 2000), lines(GeneratorConfig{vocabulary: 100, ..config(1)}, 2000));
+
+    let (mut first, mut second) = (Vec::new(), Vec::new());
+    generator::write_queries(config(3), 500, &mut first).unwrap();
+    generator::write_queries(config(3), 500, &mut second).unwrap();
+    assert_eq!(first, second);
+    let written = String::from_utf8(first).unwrap();
+    assert_eq!(written.lines().next(), Some("500"));
+    assert_eq!(written.lines().skip(1).collect::<Vec<_>>(), lines(config(3), 500));
+}
+
+#[test]
+fn queries_are_in_the_parser_grammar() {
+    for line in lines(config(4), 2000) {
+        assert!(runner::parse_line(&line).is_ok(), "{}", line);
+    }
+}
+
+#[test]
+fn configs_which_cannot_generate_are_refused() {
+    assert_eq!(GeneratorConfig::new().check(), Ok(()));
+    assert!(GeneratorConfig{add_weight: 0, done_weight: 0, search_weight: 0, ..GeneratorConfig::new()}.check().is_err());
+    assert!(GeneratorConfig{vocabulary: 0, ..GeneratorConfig::new()}.check().is_err());
+    assert!(GeneratorConfig{item_tags: (3, 1), ..GeneratorConfig::new()}.check().is_err());
+    assert!(GeneratorConfig{tag_term_chance: 1.5, ..GeneratorConfig::new()}.check().is_err());
+}
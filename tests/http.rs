@@ -0,0 +1,172 @@
+use todo_swamp::*;
+
+use std::io::prelude::*;
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serde_json::{json, Value};
+use tiny_http::Server;
+
+fn start<T: TodoLister + Send + 'static>(tl: T) -> SocketAddr {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let addr = server.server_addr().to_ip().unwrap();
+    thread::spawn(move || http::serve(server, tl));
+    addr
+}
+
+//makes a single request, returning the status code and parsed json body
+fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}", method, path, body.len(), body).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().unwrap();
+    let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+    (status, serde_json::from_str(body).unwrap())
+}
+
+const MILK: &str = r#"{"description": ["buy", "milk"], "tags": ["shopping"]}"#;
+const CAFE: &str = r#"{"description": ["Café", "au", "lait"]}"#;
+
+//a server for a list holding the items posted to it
+fn start_with(items: &[&str]) -> SocketAddr {
+    let addr = start(TodoList::new());
+    for item in items {
+        assert_eq!(request(addr, "POST", "/items", item).0, 201);
+    }
+    addr
+}
+
+#[test]
+fn items_are_added_and_got() {
+    let addr = start(TodoList::new());
+    assert_eq!(request(addr, "POST", "/items", MILK),
+        (201, json!({"index": 0, "description": ["buy", "milk"], "tags": ["shopping"], "done": false})));
+    assert_eq!(request(addr, "POST", "/items", CAFE).0, 201);
+    assert_eq!(request(addr, "GET", "/items/1", ""),
+        (200, json!({"index": 1, "description": ["Café", "au", "lait"], "tags": [], "done": false})));
+}
+
+#[test]
+fn searches_find_matching_items() {
+    let addr = start_with(&[MILK, CAFE]);
+    assert_eq!(request(addr, "GET", "/search?q=caf%C3%A9+lt", "").1, json!([
+        {"index": 1, "description": ["Café", "au", "lait"], "tags": [], "done": false},
+    ]));
+    assert_eq!(request(addr, "GET", "/search?q=%23shop", "").1.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn done_items_are_no_longer_found() {
+    let addr = start_with(&[MILK, CAFE]);
+    assert_eq!(request(addr, "POST", "/items/0/done", ""),
+        (200, json!({"index": 0, "description": ["buy", "milk"], "tags": ["shopping"], "done": true})));
+    assert_eq!(request(addr, "GET", "/search?q=%23shop", ""), (200, json!([])));
+}
+
+#[test]
+fn counts_and_stats_follow_dones() {
+    let addr = start_with(&[MILK, CAFE]);
+    assert_eq!(request(addr, "GET", "/count?q=i", ""), (200, json!({"count": 2})));
+    request(addr, "POST", "/items/0/done", "");
+    assert_eq!(request(addr, "GET", "/count?q=i", ""), (200, json!({"count": 1})));
+    assert_eq!(request(addr, "GET", "/stats", ""), (200, json!({
+        "items": {"open": 1, "done": 1},
+        "tags": {"shopping": {"open": 0, "done": 1}},
+    })));
+}
+
+#[test]
+fn tags_and_completions_come_from_open_items() {
+    let addr = start_with(&[MILK, CAFE]);
+    assert_eq!(request(addr, "GET", "/tags?prefix=SH", ""), (200, json!({"shopping": 1})));
+    request(addr, "POST", "/items/0/done", "");
+    assert_eq!(request(addr, "GET", "/tags?prefix=SH", ""), (200, json!({"shopping": 0})));
+    assert_eq!(request(addr, "GET", "/tags?prefix=x", ""), (200, json!({})));
+    assert_eq!(request(addr, "GET", "/complete?q=ca", ""), (200, json!(["café"])));
+}
+
+#[test]
+fn tags_are_edited() {
+    let addr = start_with(&[MILK, CAFE]);
+    assert_eq!(request(addr, "PATCH", "/items/1/tags", r#"{"add": ["drinks", "urgent"]}"#),
+        (200, json!({"index": 1, "description": ["Café", "au", "lait"], "tags": ["drinks", "urgent"], "done": false})));
+    assert_eq!(request(addr, "PATCH", "/items/1/tags", r#"{"remove": ["URGENT"]}"#).1["tags"], json!(["drinks"]));
+    assert_eq!(request(addr, "GET", "/count?q=%23dr", ""), (200, json!({"count": 1})));
+}
+
+#[test]
+fn views_are_saved_and_searched() {
+    let addr = start_with(&[MILK, r#"{"description": ["Café", "au", "lait"], "tags": ["drinks"]}"#]);
+    assert_eq!(request(addr, "PUT", "/views/drinks?q=%23drinks+limit%3A5", ""), (200, json!({"name": "drinks", "search": "#drinks limit:5"})));
+    assert_eq!(request(addr, "GET", "/views/drinks", "").1.as_array().unwrap().len(), 1);
+    assert_eq!(request(addr, "GET", "/search?q=%40drinks+lt", "").1.as_array().unwrap().len(), 1);
+    assert_eq!(request(addr, "GET", "/count?q=%40drinks+milk", "").1, json!({"count": 0}));
+}
+
+#[test]
+fn bad_requests_are_rejected() {
+    let addr = start(TodoList2::new());
+    assert_eq!(request(addr, "GET", "/items/0", "").0, 404);
+    assert_eq!(request(addr, "POST", "/items/3/done", "").0, 404);
+    assert_eq!(request(addr, "GET", "/items/minus-one", "").0, 400);
+    assert_eq!(request(addr, "POST", "/items", r#"{"description": []}"#).0, 400);
+    assert_eq!(request(addr, "POST", "/items", r#"{"description": ["two words"]}"#).0, 400);
+    assert_eq!(request(addr, "POST", "/items", "not json").0, 400);
+    assert_eq!(request(addr, "GET", "/search?q=", "").0, 400);
+    assert_eq!(request(addr, "GET", "/search", "").0, 400);
+    assert_eq!(request(addr, "GET", "/count?q=limit%3A1", "").0, 400);
+    let (status, body) = request(addr, "GET", "/search?q=milk+sort%3Apriority", "");
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().starts_with("unsupported sort order"));
+    assert_eq!(request(addr, "GET", "/search?q=milk+limit%3A99999999999999999999999", "").0, 400);
+    assert_eq!(request(addr, "POST", "/stats", "").0, 405);
+    assert_eq!(request(addr, "GET", "/complete", "").0, 400);
+    assert_eq!(request(addr, "DELETE", "/items/0", "").0, 405);
+    assert_eq!(request(addr, "PATCH", "/items/0/tags", r#"{"add": ["home"]}"#).0, 404);
+    assert_eq!(request(addr, "PATCH", "/items/0/tags", r##"{"add": ["#home"]}"##).0, 400);
+    assert_eq!(request(addr, "GET", "/items/0/tags", "").0, 405);
+    assert_eq!(request(addr, "GET", "/views/caf%C3%A9", ""), (404, json!({"error": "no search is saved as @café"})));
+    assert_eq!(request(addr, "GET", "/views/%FF", "").0, 400);
+    assert_eq!(request(addr, "GET", "/search?q=%40nope", "").0, 400);
+    assert_eq!(request(addr, "PUT", "/views/mine?q=%40nope", "").0, 400);
+    assert_eq!(request(addr, "POST", "/views/mine", "").0, 405);
+    let (status, body) = request(addr, "GET", "/nowhere", "");
+    assert_eq!(status, 404);
+    assert!(body["error"].is_string());
+}
+
+#[test]
+fn oversized_bodies_are_refused() {
+    let addr = start(TodoList::new());
+    //padding a body with whitespace keeps it valid json, so only its size can be wrong
+    let padded = |size: usize| {
+        let item = r#"{"description": ["buy", "milk"]}"#;
+        format!("{}{}", item, " ".repeat(size - item.len()))
+    };
+    assert_eq!(request(addr, "POST", "/items", &padded(64 * 1024)).0, 201);
+    let (status, body) = request(addr, "POST", "/items", &padded(64 * 1024 + 1));
+    assert_eq!(status, 413);
+    assert!(body["error"].is_string());
+
+    //without a Content-Length the body is read until it goes past the limit
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let body = padded(100 * 1024);
+    write!(stream, "POST /items HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n0\r\n\r\n", body.len(), body).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert_eq!(&response[9..12], "413");
+    assert_eq!(request(addr, "GET", "/count?q=milk", "").1, json!({"count": 1}));
+}
+
+//refused before the server starts listening
+#[test]
+fn server_shards_need_the_sharded_backend() {
+    Command::cargo_bin("http_server").unwrap().args(["--backend", "trie5", "--shards", "2"]).assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("--shards can only be used with --backend sharded"));
+    Command::cargo_bin("http_server").unwrap().args(["--backend", "sharded", "--shards", "0"]).assert().failure().code(2);
+}
@@ -0,0 +1,108 @@
+use todo_swamp::*;
+use todo_swamp::cache::CachedTodoList;
+use todo_swamp::generator::{Generator, GeneratorConfig};
+use todo_swamp::memory::MemoryUsage;
+use todo_swamp::sharded::ShardedTodoList;
+use todo_swamp::views::Views;
+
+use rustc_hash::FxHashSet;
+
+fn fill<T: TodoLister>(tl: &mut T) {
+    for i in 0..100 {
+        tl.push(vec![Word::new("water"), Word::new(&format!("plant{}", i))], vec![Tag::new("home")]);
+    }
+}
+
+fn usage<T: TodoLister + Default>() -> (MemoryUsage, MemoryUsage) {
+    let mut tl = T::default();
+    let empty = tl.memory_usage();
+    fill(&mut tl);
+    (empty, tl.memory_usage())
+}
+
+#[test]
+fn lists_grow_with_their_items() {
+    for (empty, full) in [usage::<TodoList>(), usage::<TodoList2>(), usage::<ShardedTodoList>()] {
+        assert_eq!(empty.items, 0);
+        assert!(full.items > 100 * std::mem::size_of::<TodoItem>());
+        assert_eq!((full.nodes, full.node_count, full.maps, full.id_sets), (0, 0, 0, 0));
+    }
+}
+
+#[test]
+fn tries_count_every_node() {
+    for (empty, full) in [usage::<TriedoList<Trie1>>(), usage::<TriedoList<Trie3>>(), usage::<TriedoList<Trie4>>(), usage::<TriedoList<Trie5>>()] {
+        assert_eq!(empty.node_count, 2); //the roots of the word and tag tries
+        //"water" and "plant" make chains of 5 under the root, "0" to "99" hang 10 first digits and 90 second digits below "plant",
+        //and "home" makes a chain of 4 in the tag trie
+        assert_eq!(full.node_count, 2 + 5 + 5 + 100 + 4);
+        assert!(full.maps > 0 && full.id_sets > 0);
+        assert_eq!(full.total(), full.items + full.nodes + full.maps + full.id_sets + full.other);
+    }
+}
+
+fn generated<T: TodoLister + Default>(count: usize) -> T {
+    let config = GeneratorConfig {
+        done_weight: 0,
+        search_weight: 0,
+        ..GeneratorConfig::new()
+    };
+    let mut tl = T::default();
+    let mut views = Views::new();
+    for line in Generator::new(config).take(count) {
+        runner::run_line(&line, &mut tl, &mut views);
+    }
+    tl
+}
+
+#[test]
+fn bitmaps_hold_ids_in_less_memory() {
+    let trie3 = generated::<TriedoList<Trie3>>(5000).memory_usage();
+    let trie4 = generated::<TriedoList<Trie4>>(5000).memory_usage();
+    let trie5 = generated::<TriedoList<Trie5>>(5000).memory_usage();
+    //the same items make the same nodes whatever holds each node's ids
+    assert_eq!(trie5.node_count, trie3.node_count);
+    assert_eq!(trie5.node_count, trie4.node_count);
+    assert!(trie5.id_sets * 2 < trie3.id_sets.min(trie4.id_sets));
+    assert!(trie5.total() < trie3.total().min(trie4.total()));
+}
+
+//a trie small enough to fill, standing in for a Trie5 with 2^32 items in it
+#[derive(Default)]
+struct SmallTrie(Trie3);
+impl Trie for SmallTrie {
+    const MAX_ID: u64 = 1;
+    fn add(&mut self, id: u64, inserts: Vec<&str>) {
+        assert!(id <= Self::MAX_ID);
+        self.0.add(id, inserts)
+    }
+    fn search(&self, searches: Vec<&str>, filter: Option<&FxHashSet<u64>>) -> FxHashSet<u64> {
+        self.0.search(searches, filter)
+    }
+    fn delete(&mut self, id: u64) {
+        self.0.delete(id)
+    }
+    fn remove(&mut self, id: u64, removes: Vec<&str>, kept: Vec<&str>) {
+        self.0.remove(id, removes, kept)
+    }
+    fn memory_usage(&self) -> MemoryUsage {
+        self.0.memory_usage()
+    }
+    fn estimate(&self, search: &str) -> u64 {
+        self.0.estimate(search)
+    }
+}
+
+#[test]
+fn full_lists_refuse_items() {
+    assert!(!TriedoList::<Trie5>::new().is_full());
+    let mut tl = CachedTodoList::new(TriedoList::<SmallTrie>::new());
+    let mut views = Views::new();
+    assert!(runner::try_run_line("add \"buy milk\"", &mut tl, &mut views).is_ok());
+    assert!(!tl.is_full());
+    assert!(runner::try_run_line("add \"buy bread\"", &mut tl, &mut views).is_ok());
+    assert!(tl.is_full());
+    assert!(runner::try_run_line("add \"buy eggs\"", &mut tl, &mut views).is_err());
+    assert_eq!(tl.len(), 2);
+    assert_eq!(runner::try_run_line("count buy", &mut tl, &mut views).unwrap().to_string(), "2");
+}
@@ -0,0 +1,89 @@
+use todo_swamp::*;
+use todo_swamp::observer::{Event, ObservedTodoList};
+use todo_swamp::views::Views;
+
+use std::sync::{Arc, Mutex};
+
+fn search(terms: &str) -> SearchParams {
+    parser::search_terms(terms).unwrap().1
+}
+
+fn run<T: TodoLister>(tl: &mut T, line: &str) {
+    runner::try_run_line(line, tl, &mut Views::new()).unwrap();
+}
+
+#[test]
+fn callbacks_hear_every_change() {
+    let mut tl = ObservedTodoList::new(TriedoList::<Trie5>::new());
+    let heard = Arc::new(Mutex::new(Vec::new()));
+    let events = Arc::clone(&heard);
+    tl.subscribe(None, &Views::new(), move |event| events.lock().unwrap().push(event.clone())).unwrap();
+    run(&mut tl, "add \"buy milk\" #shopping");
+    run(&mut tl, "tag 0 +#urgent");
+    run(&mut tl, "tag 0 +#urgent"); //nothing changed
+    run(&mut tl, "done 0");
+    run(&mut tl, "done 0"); //already done
+    let milk = |tags: Vec<&str>, done| TodoItem::new(Index::new(0), vec![Word::new("buy"), Word::new("milk")], Tag::from_strings(tags), done);
+    assert_eq!(*heard.lock().unwrap(), [
+        Event::Added(milk(vec!["shopping"], false)),
+        Event::Edited(milk(vec!["shopping", "urgent"], false)),
+        Event::Done(Index::new(0)),
+    ]);
+}
+
+#[test]
+fn filtered_channels_only_hear_about_matching_items() {
+    let mut tl = ObservedTodoList::new(TodoList::new());
+    let mut views = Views::new();
+    views.save("mine", search("#mine")).unwrap();
+    let (_, bugs) = tl.channel(Some(search("@mine #bug")), &views).unwrap();
+    let (_, all) = tl.channel(None, &views).unwrap();
+    run(&mut tl, "add \"fix login\" #bug #Mine");
+    run(&mut tl, "add \"fix layout\" #bug");
+    run(&mut tl, "add \"write docs\" #mine");
+    run(&mut tl, "tag add #bug where docs"); //comes to match
+    run(&mut tl, "tag 0 -#bug"); //stops matching, which is still heard about
+    run(&mut tl, "tag 1 +#urgent");
+    run(&mut tl, "done where fix");
+    let heard: Vec<String> = bugs.try_iter().map(|event| match event {
+        Event::Added(item) | Event::Edited(item) => item.to_string(),
+        Event::Done(idx) => format!("done {}", idx),
+    }).collect();
+    assert_eq!(heard, ["0 \"fix login\" #bug #Mine", "2 \"write docs\" #mine #bug", "0 \"fix login\" #Mine"]);
+    assert_eq!(all.try_iter().count(), 8);
+}
+
+//a filter naming a view keeps the terms the view had when the subscription was made
+#[test]
+fn filters_expand_views_when_subscribing() {
+    let mut tl = ObservedTodoList::new(TodoList::new());
+    let mut views = Views::new();
+    views.save("mine", search("#mine")).unwrap();
+    let (_, mine) = tl.channel(Some(search("@mine")), &views).unwrap();
+    views.save("mine", search("#theirs")).unwrap();
+    let (_, resubscribed) = tl.channel(Some(search("@mine")), &views).unwrap();
+    run(&mut tl, "add \"fix login\" #mine");
+    run(&mut tl, "add \"fix layout\" #theirs");
+    assert_eq!(mine.try_iter().collect::<Vec<_>>(), [Event::Added(tl.get(Index::new(0)).unwrap().clone())]);
+    assert_eq!(resubscribed.try_iter().collect::<Vec<_>>(), [Event::Added(tl.get(Index::new(1)).unwrap().clone())]);
+}
+
+#[test]
+fn subscriptions_end() {
+    let tl = ObservedTodoList::new(TodoList2::new());
+    let views = Views::new();
+    let id = tl.subscribe(None, &views, |_| ()).unwrap();
+    let (_, receiver) = tl.channel(Some(search("milk")), &views).unwrap();
+    assert!(tl.channel(Some(search("@nope")), &views).is_err());
+    assert_eq!(tl.subscriptions(), 2);
+    assert!(tl.unsubscribe(id));
+    assert!(!tl.unsubscribe(id));
+
+    //a channel nobody is listening to is dropped the next time it would be sent an event
+    drop(receiver);
+    let mut tl = tl;
+    run(&mut tl, "add \"buy bread\"");
+    assert_eq!(tl.subscriptions(), 1);
+    run(&mut tl, "add \"buy milk\"");
+    assert_eq!(tl.subscriptions(), 0);
+}
@@ -0,0 +1,91 @@
+use todo_swamp::*;
+use todo_swamp::cache::CachedTodoList;
+use todo_swamp::session::SearchSession;
+use todo_swamp::sharded::ShardedTodoList;
+
+fn search(terms: &str) -> SearchParams {
+    parser::search_terms(terms).unwrap().1
+}
+
+fn found(items: Vec<&TodoItem>) -> Vec<u64> {
+    items.iter().map(|item| item.index.value()).collect()
+}
+
+//items 0 to 19 all match "milk", every third one tagged #shop
+fn filled<T: TodoLister>(mut tl: T) -> T {
+    for i in 0..20 {
+        let tags = if i % 3 == 0 { vec![Tag::new("shop")] } else { Vec::new() };
+        tl.push(vec![Word::new("buy"), Word::new("milk")], tags);
+    }
+    tl
+}
+
+fn check_pages<T: TodoLister>(tl: &T) {
+    assert_eq!(found(tl.search(search("milk limit:3"))), [0, 1, 2]);
+    assert_eq!(found(tl.search(search("milk limit:3 offset:4"))), [4, 5, 6]);
+    assert_eq!(found(tl.search(search("milk offset:17"))), [17, 18, 19]);
+    assert_eq!(found(tl.search(search("milk sort:-index limit:2"))), [19, 18]);
+    assert_eq!(found(tl.search(search("milk sort:-index offset:18"))), [1, 0]);
+    assert_eq!(found(tl.search(search("milk #shop sort:index"))), [0, 3, 6, 9, 12, 15, 18]);
+    assert_eq!(found(tl.search(search("milk #shop sort:-index limit:3 offset:1"))), [15, 12, 9]);
+    assert!(tl.search(search("milk limit:0")).is_empty());
+    assert!(tl.search(search("milk offset:20")).is_empty());
+    assert_eq!(tl.search(search("milk")).len(), 20);
+}
+
+#[test]
+fn every_backend_pages_the_same() {
+    check_pages(&filled(TodoList::new()));
+    check_pages(&filled(TodoList2::new()));
+    check_pages(&filled(TriedoList::<Trie1>::new()));
+    check_pages(&filled(TriedoList::<Trie3>::new()));
+    check_pages(&filled(TriedoList::<Trie5>::new()));
+    check_pages(&filled(ShardedTodoList::with_shards(3)));
+    check_pages(&filled(CachedTodoList::new(TriedoList::<Trie4>::new())));
+}
+
+//"a" is matched at both "pea" and "gra", so the tries hand those items' ids on twice, but each is still found once
+#[test]
+fn items_matching_more_than_one_word_are_found_once() {
+    fn check<T: TodoLister>(mut tl: T) {
+        for description in &["pear grape", "apple", "pear grape", "kiwi"] {
+            tl.push(description.split(' ').map(Word::new).collect(), Vec::new());
+        }
+        assert_eq!(found(tl.search(search("a limit:2"))), [0, 1]);
+        assert_eq!(found(tl.search(search("a sort:-index limit:2"))), [2, 1]);
+        assert_eq!(found(tl.search(search("a sort:index"))), [0, 1, 2]);
+        assert_eq!(tl.count(search("a")), 3);
+    }
+    check(TriedoList::<Trie1>::new());
+    check(TriedoList::<Trie2>::new());
+    check(TriedoList::<Trie3>::new());
+    check(TriedoList::<Trie4>::new());
+    check(TriedoList::<Trie5>::new());
+}
+
+#[test]
+fn closer_matches_score_higher() {
+    let mut tl = TriedoList::<Trie5>::new();
+    for description in &["smile", "milky way", "mail", "mil", "almilk"] {
+        tl.push(description.split(' ').map(Word::new).collect(), Vec::new());
+    }
+    //whole word, then prefixes, then a run of letters, then letters spread out, with ties in index order
+    assert_eq!(found(tl.search(search("mil sort:score"))), [3, 1, 0, 4, 2]);
+    assert_eq!(found(tl.search(search("mil sort:score limit:2 offset:2"))), [0, 4]);
+    assert_eq!(found(tl.search(search("milk way sort:score"))), [1]);
+}
+
+//pages are cut from every match, so a done before the page moves it along even though the page never held that item
+#[test]
+fn cached_and_refined_pages_follow_changes() {
+    let mut tl = filled(CachedTodoList::new(TodoList::new()));
+    assert_eq!(found(tl.search(search("milk limit:2 offset:2"))), [2, 3]);
+    tl.done_with_index(Index::new(0));
+    assert_eq!(found(tl.search(search("milk limit:2 offset:2"))), [3, 4]);
+    assert_eq!(found(tl.search(search("milk limit:2"))), [1, 2]);
+
+    let tl = filled(TodoList::new());
+    let mut session = SearchSession::new();
+    assert_eq!(found(session.search(&tl, search("mi limit:1"))), [0]);
+    assert_eq!(found(session.search(&tl, search("mil #s sort:-index limit:2"))), [18, 15]);
+}
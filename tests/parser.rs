@@ -0,0 +1,193 @@
+use todo_swamp::*;
+
+use proptest::prelude::*;
+
+//letters, digits, combining marks and dashes from any script, whatever the parser accepts as a word
+fn word() -> impl Strategy<Value = String> {
+    "[\\p{L}\\p{N}\\p{M}-]{1,8}".prop_filter("not a word", |w| parser::is_word(w))
+}
+
+fn search_word_or_tag() -> impl Strategy<Value = SearchWordOrTag> {
+    prop_oneof![
+        word().prop_map(SearchWordOrTag::RawWord),
+        word().prop_map(SearchWordOrTag::RawTag),
+    ]
+}
+
+fn search_options() -> impl Strategy<Value = SearchOptions> {
+    let sort = prop_oneof![Just(SortOrder::Index), Just(SortOrder::IndexDescending), Just(SortOrder::Score)];
+    (prop::option::of(any::<usize>()), prop::option::of(any::<usize>()), prop::option::of(sort))
+        .prop_map(|(limit, offset, sort)| SearchOptions{limit, offset, sort})
+}
+
+fn search_params() -> impl Strategy<Value = SearchParams> {
+    (prop::collection::vec(search_word_or_tag(), 1..6), search_options(), prop::collection::vec(word(), 0..2))
+        .prop_map(|(params, options, views)| SearchParams{params, options, views})
+}
+
+fn query() -> impl Strategy<Value = Query> {
+    prop_oneof![
+        (prop::collection::vec(word(), 1..6), prop::collection::vec(word(), 0..4)).prop_map(|(description, tags)| {
+            Query::Add(description.iter().map(|w| Word::new(w)).collect(), tags.iter().map(|t| Tag::new(t)).collect())
+        }),
+        any::<u64>().prop_map(|i| Query::Done(Index::new(i))),
+        search_params().prop_map(Query::Search),
+        search_params().prop_map(Query::Count),
+        Just(Query::Stats),
+        prop::option::of(word()).prop_map(Query::Tags),
+        search_params().prop_map(Query::DoneWhere),
+        (word(), search_params()).prop_map(|(name, sp)| Query::Save(name, sp)),
+        word().prop_map(Query::View),
+        (any::<bool>(), prop::collection::vec(word(), 1..4), search_params()).prop_map(|(adding, tags, sp)| {
+            Query::TagWhere(if adding { TagChange::Add } else { TagChange::Remove }, tags.iter().map(|t| Tag::new(t)).collect(), sp)
+        }),
+        (any::<u64>(), prop::collection::vec(word(), 0..3), prop::collection::vec(word(), 0..3))
+            .prop_filter("no tags to change", |(_, add, remove)| !add.is_empty() || !remove.is_empty())
+            .prop_map(|(i, add, remove)| Query::EditTags(Index::new(i), TagEdit{add: Tag::from_strings(add.iter().map(String::as_str).collect()), remove: Tag::from_strings(remove.iter().map(String::as_str).collect())})),
+    ]
+}
+
+fn parse(line: &str) -> Result<Query, QueryError> {
+    runner::parse_line(line)
+}
+
+proptest! {
+    #[test]
+    fn printed_queries_parse_back(q in query()) {
+        prop_assert_eq!(parse(&q.to_string()), Ok(q));
+    }
+
+    //the keyword and first tag can be followed by a tab instead of a space, and nothing else
+    #[test]
+    fn tab_can_replace_single_spaces(q in query()) {
+        let line = q.to_string();
+        let keyword_end = match line.find(' ') {
+            Some(i) => i,
+            None => return Ok(()), //stats and tags can take nothing after their keywords
+        };
+        let tabbed = format!("{}\t{}", &line[..keyword_end], &line[keyword_end + 1..]);
+        prop_assert_eq!(parse(&tabbed), Ok(q.clone()));
+        if let Query::Add(_, tags) = &q {
+            if !tags.is_empty() {
+                let tabbed = line.replacen("\" #", "\"\t#", 1);
+                prop_assert_eq!(parse(&tabbed), Ok(q.clone()));
+            }
+        }
+        let doubled = format!("{}  {}", &line[..keyword_end], &line[keyword_end + 1..]);
+        prop_assert!(parse(&doubled).is_err());
+    }
+
+    #[test]
+    fn arbitrary_lines_do_not_panic(line in "\\PC{0,40}") {
+        let _ = parse(&line);
+    }
+
+    #[test]
+    fn huge_indices_are_errors(digits in "[1-9][0-9]{20,30}") {
+        let line = format!("done {}", digits);
+        prop_assert!(parse(&line).is_err());
+    }
+}
+
+#[test]
+fn descriptions_need_a_word() {
+    assert!(parse("add \"\"").is_err());
+    assert!(parse("add \"\" #home").is_err());
+    assert_eq!(parse("add \"milk\""), Ok(Query::Add(vec![Word::new("milk")], Vec::new())));
+}
+
+#[test]
+fn tags_need_whitespace_before_them() {
+    assert_eq!(parse("add \"milk\"#shopping"), Ok(Query::Add(vec![Word::new("milk")], Vec::new())));
+    assert_eq!(parse("add \"milk\" #shopping #urgent").unwrap().to_string(), "add \"milk\" #shopping #urgent");
+}
+
+#[test]
+fn search_options_go_anywhere_among_terms() {
+    let expected = SearchParams {
+        params: vec![SearchWordOrTag::RawWord(String::from("milk")), SearchWordOrTag::RawTag(String::from("shop"))],
+        options: SearchOptions{limit: Some(10), offset: Some(20), sort: Some(SortOrder::IndexDescending)},
+        views: Vec::new(),
+    };
+    assert_eq!(parse("search limit:10 milk sort:-index #shop offset:20"), Ok(Query::Search(expected.clone())));
+    assert_eq!(parse("search milk #shop limit:10 offset:20 sort:-index"), Ok(Query::Search(expected)));
+    //words which only start like an option are still words
+    assert_eq!(parse("search limited"), Ok(Query::Search(SearchParams::new(vec![SearchWordOrTag::RawWord(String::from("limited"))]))));
+}
+
+//a bad option fails the whole search rather than being read as a word with the rest of the line dropped
+#[test]
+fn search_options_are_checked() {
+    let bad = ["limit:5", "milk limit:5 limit:6", "milk sort:score sort:index", "milk sort:priority", "milk sort:due", "sort:due",
+        "milk sort:indexes", "milk sort:", "milk offset:-1", "milk limit:99999999999999999999999", "milk limit:5x", "milk #shop!"];
+    for terms in &bad {
+        assert!(parser::search_terms(terms).is_err(), "{}", terms);
+        for keyword in &["search", "count", "done where", "tag add #a where", "save mine"] {
+            let line = format!("{} {}", keyword, terms);
+            assert!(parse(&line).is_err(), "{}", line);
+        }
+    }
+}
+
+#[test]
+fn priority_and_due_sorts_are_unsupported() {
+    for line in &["search milk sort:priority", "count sort:due milk", "done where milk sort:due"] {
+        let e = parse(line).unwrap_err();
+        assert!(e.0.starts_with("unsupported sort order"), "{}: {}", line, e);
+    }
+    assert!(!parse("search milk sort:size").unwrap_err().0.starts_with("unsupported sort order"));
+}
+
+#[test]
+fn only_add_ignores_what_follows_it() {
+    for line in &["done 3 4", "done 3x", "search milk ", "count milk, bread", "tag 3 +#a-#b", "tag add #a where milk!", "save mine milk?"] {
+        assert!(parse(line).is_err(), "{}", line);
+    }
+}
+
+#[test]
+fn stats_takes_nothing_else() {
+    assert_eq!(parse("stats"), Ok(Query::Stats));
+    assert!(parse("stats #home").is_err());
+    assert!(parse("statsmilk").is_err());
+    assert!(parse("count").is_err());
+}
+
+#[test]
+fn tag_prefixes_can_have_a_hash() {
+    assert_eq!(parse("tags"), Ok(Query::Tags(None)));
+    assert_eq!(parse("tags #ho"), Ok(Query::Tags(Some(String::from("ho")))));
+    assert_eq!(parse("tags ho"), Ok(Query::Tags(Some(String::from("ho")))));
+    assert!(parse("tags ho me").is_err());
+    assert!(parse("tags ").is_err());
+}
+
+#[test]
+fn bulk_queries_need_where_and_tags() {
+    let milk = SearchParams::new(vec![SearchWordOrTag::RawWord(String::from("milk"))]);
+    assert_eq!(parse("done where milk"), Ok(Query::DoneWhere(milk.clone())));
+    assert_eq!(parse("tag remove #a #b where milk"), Ok(Query::TagWhere(TagChange::Remove, Tag::from_strings(vec!["a", "b"]), milk)));
+    for line in &["done where", "done milk", "tag add where milk", "tag add #a milk", "tag add #a where", "tag #a where milk", "tag change #a where milk"] {
+        assert!(parse(line).is_err(), "{}", line);
+    }
+}
+
+#[test]
+fn tag_edits_mix_additions_and_removals() {
+    let edit = TagEdit{add: Tag::from_strings(vec!["a", "c"]), remove: Tag::from_strings(vec!["b"])};
+    assert_eq!(parse("tag 3 +#a -#b +#c"), Ok(Query::EditTags(Index::new(3), edit)));
+    for line in &["tag 3", "tag 3 #a", "tag 3 +a", "tag +#a", "tag -1 +#a"] {
+        assert!(parse(line).is_err(), "{}", line);
+    }
+}
+
+#[test]
+fn saved_searches_can_stand_in_for_terms() {
+    let mine = SearchParams{views: vec![String::from("mine")], ..SearchParams::new(Vec::new())};
+    assert_eq!(parse("search @mine"), Ok(Query::Search(mine.clone())));
+    assert_eq!(parse("save bugs @mine #bug").unwrap().to_string(), "save bugs #bug @mine");
+    assert_eq!(parse("view mine"), Ok(Query::View(String::from("mine"))));
+    for line in &["search @", "save mine", "save @mine #bug", "view", "view mine #bug", "search limit:1 @"] {
+        assert!(parse(line).is_err(), "{}", line);
+    }
+}
@@ -0,0 +1,48 @@
+use todo_swamp::*;
+use todo_swamp::planner::{self, PlannedTerm};
+
+fn search(terms: &str) -> SearchParams {
+    parser::search_terms(terms).unwrap().1.normalised()
+}
+
+fn order<'a>(plan: &[PlannedTerm<'a>]) -> Vec<(&'a str, bool)> {
+    plan.iter().map(|t| (t.term, t.is_tag)).collect()
+}
+
+fn tries() -> (Trie3, Trie3) {
+    let (mut words, mut tags) = (Trie3::default(), Trie3::default());
+    for id in 0..100 {
+        words.add(id, vec!["water", "plants"]);
+        tags.add(id, if id == 7 { vec!["urgent", "home"] } else { vec!["home"] });
+    }
+    words.add(100, vec!["wash"]);
+    (words, tags)
+}
+
+//equal estimates go to the longer term, whether it is a word or a tag
+#[test]
+fn rare_terms_run_first_whether_words_or_tags() {
+    let (words, tags) = tries();
+    let sp = search("wa pl #urg #h");
+    let plan = planner::plan(&sp, &words, &tags);
+    assert_eq!(order(&plan), [("urg", true), ("pl", false), ("h", true), ("wa", false)]);
+    assert_eq!(plan.iter().map(|t| t.estimate).collect::<Vec<_>>(), [1, 100, 100, 101]);
+}
+
+#[test]
+fn deeper_prefixes_are_rarer() {
+    let (words, tags) = tries();
+    let sp = search("w was wat");
+    assert_eq!(order(&planner::plan(&sp, &words, &tags)), [("was", false), ("wat", false), ("w", false)]);
+}
+
+//a term can match inside words no word starts with, so where its characters lead nowhere
+//it's estimated by the deepest node they do reach, the root if not even its first letter starts a word
+#[test]
+fn terms_leading_nowhere_are_bounded_by_where_they_stop() {
+    let (words, tags) = tries();
+    let sp = search("ts pla wax #urgx");
+    let plan = planner::plan(&sp, &words, &tags);
+    assert_eq!(order(&plan), [("urgx", true), ("pla", false), ("wax", false), ("ts", false)]);
+    assert_eq!(plan.iter().map(|t| t.estimate).collect::<Vec<_>>(), [1, 100, 101, 101]);
+}
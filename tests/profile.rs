@@ -0,0 +1,93 @@
+use todo_swamp::*;
+use todo_swamp::profile::{self, Profiler};
+use todo_swamp::views::Views;
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+//a slow query log the test can read back
+#[derive(Clone, Default)]
+struct SharedLog(Arc<Mutex<Vec<u8>>>);
+impl Write for SharedLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+const QUERIES: [&str; 5] = ["add \"water the plants\" #home", "add \"buy milk\" #shopping", "search pl #ho", "done 7", "done 0"];
+
+fn profile<T: TodoLister>(mut tl: T, profiler: &mut Profiler) {
+    let mut views = Views::new();
+    for q in &QUERIES {
+        runner::run_line_profiled(q, &mut tl, &mut views, profiler);
+    }
+}
+
+#[test]
+fn queries_are_counted_by_kind() {
+    let mut profiler = Profiler::new(Duration::from_secs(60));
+    profile(TriedoList::<Trie3>::new(), &mut profiler);
+    let stats = profiler.stats();
+    assert_eq!((stats.adds.count, stats.searches.count, stats.dones.count, stats.errors.count), (2, 1, 1, 1));
+    assert!(stats.searches.nodes_visited > 0);
+    assert!(stats.searches.candidates > 0);
+    assert_eq!(stats.adds.nodes_visited + stats.dones.nodes_visited, 0);
+    assert_eq!(stats.slow_queries, 0);
+    assert_eq!(profile::take_counters(), profile::WorkCounters::default());
+}
+
+#[test]
+fn scanning_backends_count_every_item_as_a_candidate() {
+    let mut profiler = Profiler::new(Duration::from_secs(60));
+    profile(TodoList::new(), &mut profiler);
+    assert_eq!(profiler.stats().searches.candidates, 2);
+    assert_eq!(profiler.stats().searches.nodes_visited, 0);
+}
+
+#[test]
+fn slow_queries_are_logged() {
+    let log = SharedLog::default();
+    let mut profiler = Profiler::new(Duration::from_secs(0)).with_slow_log(Box::new(log.clone()));
+    profile(TriedoList::<Trie4>::new(), &mut profiler);
+    assert_eq!(profiler.stats().slow_queries, QUERIES.len() as u64);
+    let log = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines.len(), QUERIES.len());
+    assert!(lines[2].contains(" nodes=") && lines[2].ends_with(" search pl #ho"), "{}", lines[2]);
+}
+
+fn gathered<T: TodoLister>(tl: &T, terms: &str) -> (usize, u64) {
+    profile::take_counters();
+    let found = tl.search(parser::search_terms(terms).unwrap().1).len();
+    (found, profile::take_counters().gathered)
+}
+
+//a page in index order is picked out as the last term's trie hands on its ids one at a time, so its matches are never gathered,
+//only the ids matching the terms before it
+#[test]
+fn index_pages_are_picked_without_gathering_every_match() {
+    let mut tl = TriedoList::<Trie5>::new();
+    for i in 0..200 {
+        tl.push(vec![Word::new("water"), Word::new("plants")], Tag::from_strings(if i % 2 == 0 { vec!["home"] } else { Vec::new() }));
+    }
+    assert_eq!(gathered(&tl, "water limit:5"), (5, 0));
+    assert_eq!(gathered(&tl, "water sort:-index limit:5 offset:10"), (5, 0));
+    assert_eq!(gathered(&tl, "water #home limit:5"), (5, 100));
+    //every one of the last term's ids is still handed on and checked
+    profile::take_counters();
+    tl.search(parser::search_terms("water limit:5").unwrap().1);
+    assert_eq!(profile::take_counters().candidates, 200);
+    //sorting every match, or by score, has to hold them all
+    assert_eq!(gathered(&tl, "water"), (200, 200));
+    assert_eq!(gathered(&tl, "water #home"), (100, 200));
+    assert_eq!(gathered(&tl, "water sort:score limit:5"), (5, 200));
+
+    //and so does counting them, so that each is counted once
+    profile::take_counters();
+    assert_eq!(tl.count(parser::search_terms("water #home").unwrap().1), 100);
+    assert_eq!(profile::take_counters().gathered, 200);
+}
@@ -0,0 +1,79 @@
+use todo_swamp::*;
+
+use std::io::{prelude::*, BufReader};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+//sends a query and reads lines back up to the empty line which ends its response
+fn query(reader: &mut impl BufRead, writer: &mut impl Write, line: &str) -> Vec<String> {
+    writeln!(writer, "{}", line).unwrap();
+    writer.flush().unwrap();
+    let mut response = Vec::new();
+    loop {
+        let mut l = String::new();
+        assert_ne!(reader.read_line(&mut l).unwrap(), 0, "server closed the connection mid-response");
+        let l = l.trim_end_matches('\n');
+        if l.is_empty() {
+            return response
+        }
+        response.push(l.to_owned());
+    }
+}
+
+fn connect_tcp(port: u16) -> (BufReader<TcpStream>, TcpStream) {
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    (BufReader::new(stream.try_clone().unwrap()), stream)
+}
+
+#[test]
+fn tcp_clients_share_one_list() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || server::serve_tcp(listener, TodoList::new()));
+
+    let (mut r1, mut w1) = connect_tcp(port);
+    let (mut r2, mut w2) = connect_tcp(port);
+    assert_eq!(query(&mut r1, &mut w1, "add \"buy milk\" #shopping"), ["0"]);
+    assert_eq!(query(&mut r2, &mut w2, "add \"buy bread\" #shopping #urgent"), ["1"]);
+    assert_eq!(query(&mut r1, &mut w1, "search #shop"), ["2 item(s) found", "0 \"buy milk\" #shopping", "1 \"buy bread\" #shopping #urgent"]);
+    assert_eq!(query(&mut r2, &mut w2, "done 0"), ["done"]);
+    assert_eq!(query(&mut r1, &mut w1, "search #shop"), ["1 item(s) found", "1 \"buy bread\" #shopping #urgent"]);
+    assert_eq!(query(&mut r2, &mut w2, "search milk"), ["0 item(s) found"]);
+}
+
+#[test]
+fn errors_are_framed_and_do_not_drop_the_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || server::serve_tcp(listener, TodoList::new()));
+
+    let (mut r, mut w) = connect_tcp(port);
+    let response = query(&mut r, &mut w, "done 7");
+    assert_eq!(response.len(), 1);
+    assert!(response[0].starts_with("error: "));
+    let response = query(&mut r, &mut w, "frobnicate");
+    assert_eq!(response.len(), 1);
+    assert!(response[0].starts_with("error: "));
+    assert_eq!(query(&mut r, &mut w, "add \"still here\"\r"), ["0"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_socket_clients_share_one_list() {
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    let path = std::env::temp_dir().join(format!("todo_swamp_server_test_{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    thread::spawn(move || server::serve_unix(listener, TodoList2::new()));
+
+    let connect = || {
+        let stream = UnixStream::connect(&path).unwrap();
+        (BufReader::new(stream.try_clone().unwrap()), stream)
+    };
+    let (mut r1, mut w1) = connect();
+    let (mut r2, mut w2) = connect();
+    assert_eq!(query(&mut r1, &mut w1, "add \"write report\" #work"), ["0"]);
+    assert_eq!(query(&mut r2, &mut w2, "search rep"), ["1 item(s) found", "0 \"write report\" #work"]);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,157 @@
+use todo_swamp::*;
+use todo_swamp::concurrent::ConcurrentTodoList;
+use todo_swamp::session::{self, SearchSession};
+use todo_swamp::views::Views;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+const WORDS: [&str; 8] = ["meet", "meeting", "message", "milk", "mum", "remember", "team", "Café"];
+const TAGS: [&str; 4] = ["home", "work", "shopping", "urgent"];
+
+fn search(terms: &str) -> SearchParams {
+    parser::search_terms(terms).unwrap().1.normalised()
+}
+
+fn indices(items: Vec<&TodoItem>) -> Vec<u64> {
+    let mut indices: Vec<u64> = items.iter().map(|item| item.index.value()).collect();
+    indices.sort_unstable();
+    indices
+}
+
+#[test]
+fn longer_terms_and_extra_terms_refine() {
+    assert!(session::refines(&search("me"), &search("mee")));
+    assert!(session::refines(&search("me"), &search("mxe")));
+    assert!(session::refines(&search("me"), &search("meet #work")));
+    assert!(session::refines(&search("#wo"), &search("#work me")));
+    assert!(session::refines(&search("ME"), &search("meet")));
+    assert!(!session::refines(&search("mee"), &search("me")));
+    assert!(!session::refines(&search("me"), &search("#meet")));
+    assert!(!session::refines(&search("me #w"), &search("meet")));
+}
+
+//whatever order the searches, pushes, dones and tag edits come in, a session finds what a fresh search would
+#[test]
+fn sessions_find_what_fresh_searches_do() {
+    for seed in 0..5 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut tl = TriedoList::<Trie5>::new();
+        let mut session = SearchSession::new();
+        let mut typed = String::new();
+        for _ in 0..500 {
+            match rng.gen_range(0, 6) {
+                0 => {
+                    let (word_count, tag_count) = (rng.gen_range(1, 4), rng.gen_range(0, 3));
+                    let description: Vec<Word> = WORDS.choose_multiple(&mut rng, word_count).map(|w| Word::new(w)).collect();
+                    let tags: Vec<Tag> = TAGS.choose_multiple(&mut rng, tag_count).map(|t| Tag::new(t)).collect();
+                    tl.push(description, tags);
+                },
+                1 => {
+                    tl.done_with_index(Index::new(rng.gen_range(0, 100)));
+                },
+                2 => typed.clear(), //start typing a new search
+                3 if rng.gen_range(0, 3) == 0 => {
+                    let tag = vec![Tag::new(TAGS.choose(&mut rng).unwrap())];
+                    let edit = if rng.gen() { TagEdit{add: tag, remove: Vec::new()} } else { TagEdit{add: Vec::new(), remove: tag} };
+                    tl.edit_tags(Index::new(rng.gen_range(0, 100)), &edit);
+                    session.reset();
+                },
+                _ => {
+                    //type one more letter of a word, or start another term
+                    let word = WORDS.choose(&mut rng).unwrap().to_lowercase();
+                    match typed.split(' ').next_back() {
+                        Some(last) if !last.is_empty() && rng.gen_range(0, 4) > 0 => {
+                            let next = word.chars().nth(last.chars().count()).unwrap_or('e');
+                            typed.push(next);
+                        },
+                        _ => {
+                            if !typed.is_empty() {
+                                typed.push(' ');
+                            }
+                            if rng.gen() {
+                                typed.push('#');
+                                typed.push_str(&TAGS.choose(&mut rng).unwrap()[..1]);
+                            }
+                            else {
+                                typed.push_str(&word[..1]);
+                            }
+                        },
+                    }
+                    let sp = search(&typed);
+                    assert_eq!(indices(session.search(&tl, sp.clone())), indices(tl.search(sp)), "seed {}: {}", seed, typed);
+                },
+            }
+        }
+    }
+}
+
+#[test]
+fn refined_searches_only_look_through_previous_results() {
+    let mut tl = TodoList::new();
+    for description in &["buy milk", "meet team", "memo mum", "fix bike"] {
+        tl.push(description.split(' ').map(Word::new).collect(), Vec::new());
+    }
+    let mut session = SearchSession::new();
+    profile::take_counters();
+    assert_eq!(indices(session.search(&tl, search("me"))), [1, 2]);
+    assert_eq!(profile::take_counters().candidates, 4);
+    tl.push(vec![Word::new("meeting")], Vec::new());
+    assert_eq!(indices(session.search(&tl, search("mee"))), [1, 4]);
+    assert_eq!(profile::take_counters().candidates, 3); //two previous results and the item pushed since
+    assert_eq!(indices(session.search(&tl, search("bu"))), [0]);
+    assert_eq!(profile::take_counters().candidates, 5);
+}
+
+//a tag added since the last search can make an item match which that search passed over
+#[test]
+fn tag_edits_start_searches_afresh() {
+    let (mut tl, mut views) = (TodoList::new(), Views::new());
+    runner::run_line("add \"milk\" #home", &mut tl, &mut views);
+    runner::run_line("add \"bread\"", &mut tl, &mut views);
+    let mut session = SearchSession::new();
+    assert!(runner::try_run_line_in_session("search #h", &mut tl, &mut views, &mut session).is_ok());
+    assert!(runner::try_run_line_in_session("tag add #home where bread", &mut tl, &mut views, &mut session).is_ok());
+    profile::take_counters();
+    assert_eq!(runner::try_run_line_in_session("search #ho", &mut tl, &mut views, &mut session).map(|r| r.to_string()), Ok(String::from("2 item(s) found\n0 \"milk\" #home\n1 \"bread\" #home")));
+    assert_eq!(profile::take_counters().candidates, 2);
+}
+
+//on a shared list, a session hears of edits made from any other
+#[test]
+fn tag_edits_from_other_sessions_start_searches_afresh() {
+    let tl = ConcurrentTodoList::new(TodoList::new());
+    tl.run_line("add \"milk\" #home").unwrap();
+    tl.run_line("add \"bread\"").unwrap();
+    tl.run_line("add \"eggs\"").unwrap();
+    let (mut searching, mut editing) = (SearchSession::new(), SearchSession::new());
+    assert!(tl.run_line_in_session("search #h", &mut searching).is_ok());
+    assert!(tl.run_line_in_session("tag add #home where bread", &mut editing).is_ok());
+    profile::take_counters();
+    assert_eq!(tl.run_line_in_session("search #ho", &mut searching).map(|r| r.to_string()), Ok(String::from("2 item(s) found\n0 \"milk\" #home\n1 \"bread\" #home")));
+    assert_eq!(profile::take_counters().candidates, 3);
+    //with no edit since, the next search narrows down the last one's results again
+    assert!(tl.run_line_in_session("search #hom", &mut searching).is_ok());
+    assert_eq!(profile::take_counters().candidates, 2);
+}
+
+//edits which fail or change nothing are not heard of, so the next search still narrows down the last one's results
+#[test]
+fn failed_tag_edits_leave_searches_narrowing() {
+    let tl = ConcurrentTodoList::new(TodoList::new());
+    tl.run_line("add \"milk\" #home").unwrap();
+    tl.run_line("add \"bread\"").unwrap();
+    let mut session = SearchSession::new();
+    assert!(tl.run_line_in_session("search #h", &mut session).is_ok());
+    assert_eq!(tl.edit_tags(Index::new(5), &TagEdit{add: vec![Tag::new("home")], remove: Vec::new()}), None);
+    assert_eq!(tl.edit_tags(Index::new(0), &TagEdit{add: vec![Tag::new("home")], remove: Vec::new()}), Some(false));
+    assert!(tl.run_line("tag 7 +#home").is_err());
+    assert_eq!(tl.run_line("tag add #home where eggs").map(|r| r.to_string()), Ok(String::from("0 item(s) affected")));
+    profile::take_counters();
+    assert!(tl.run_line_in_session("search #ho", &mut session).is_ok());
+    assert_eq!(profile::take_counters().candidates, 1);
+
+    assert_eq!(tl.edit_tags(Index::new(1), &TagEdit{add: vec![Tag::new("home")], remove: Vec::new()}), Some(true));
+    profile::take_counters();
+    assert_eq!(tl.run_line_in_session("search #hom", &mut session).map(|r| r.to_string()), Ok(String::from("2 item(s) found\n0 \"milk\" #home\n1 \"bread\" #home")));
+    assert_eq!(profile::take_counters().candidates, 2);
+}
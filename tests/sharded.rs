@@ -0,0 +1,40 @@
+use todo_swamp::*;
+use todo_swamp::sharded::ShardedTodoList;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+const WORDS: [&str; 8] = ["buy", "milk", "bread", "call", "mum", "fix", "bike", "Café"];
+const TAGS: [&str; 4] = ["home", "work", "shopping", "urgent"];
+
+//unlike the tries, sharding has to give back exactly what TodoList does, in the same order
+#[test]
+fn matches_todo_list_exactly() {
+    for shard_count in [1, 2, 3, 7, 64] {
+        let mut rng = StdRng::seed_from_u64(shard_count as u64);
+        let mut expected = TodoList::new();
+        let mut sharded = ShardedTodoList::with_shards(shard_count);
+        for _ in 0..500 {
+            match rng.gen_range(0, 3) {
+                0 => {
+                    let (word_count, tag_count) = (rng.gen_range(1, 4), rng.gen_range(0, 3));
+                    let description: Vec<Word> = WORDS.choose_multiple(&mut rng, word_count).map(|w| Word::new(w)).collect();
+                    let tags: Vec<Tag> = TAGS.choose_multiple(&mut rng, tag_count).map(|t| Tag::new(t)).collect();
+                    assert_eq!(sharded.push(description.clone(), tags.clone()), expected.push(description, tags));
+                },
+                1 => {
+                    let idx = Index::new(rng.gen_range(0, 200));
+                    assert_eq!(sharded.done_with_index(idx), expected.done_with_index(idx));
+                    assert_eq!(sharded.get(idx), expected.get(idx));
+                },
+                _ => {
+                    let mut params = vec![SearchWordOrTag::RawWord(WORDS.choose(&mut rng).unwrap()[..2].to_owned())];
+                    if rng.gen() {
+                        params.push(SearchWordOrTag::RawTag(TAGS.choose(&mut rng).unwrap()[1..3].to_owned()));
+                    }
+                    let sp = SearchParams::new(params);
+                    assert_eq!(sharded.search(sp.clone()), expected.search(sp));
+                },
+            }
+        }
+    }
+}
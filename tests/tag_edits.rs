@@ -0,0 +1,66 @@
+use todo_swamp::*;
+use todo_swamp::views::Views;
+
+use rustc_hash::FxHashSet;
+
+const PROBES: [&str; 9] = ["h", "ho", "home", "hm", "hw", "homework", "ha", "at", "o"];
+
+fn found<T: Trie>(trie: &T, search: &str) -> Vec<u64> {
+    let mut ids: Vec<u64> = trie.search(vec![search], None::<&FxHashSet<u64>>).into_iter().collect();
+    ids.sort_unstable();
+    ids
+}
+
+//removing some of an id's strings leaves a trie which searches as though only the kept strings had been added
+fn check_remove<T: Trie + Default>() {
+    let mut edited = T::default();
+    edited.add(0, vec!["home", "homework", "hat"]);
+    edited.add(1, vec!["home"]);
+    edited.remove(0, vec!["homework"], vec!["home", "hat"]);
+    edited.remove(0, vec!["home"], vec!["hat"]);
+    let mut fresh = T::default();
+    fresh.add(0, vec!["hat"]);
+    fresh.add(1, vec!["home"]);
+    for probe in &PROBES {
+        assert_eq!(found(&edited, probe), found(&fresh, probe), "{}", probe);
+    }
+
+    //a kept string which is a prefix of a removed one keeps the id on the way down
+    let mut edited = T::default();
+    edited.add(0, vec!["home", "homework"]);
+    edited.remove(0, vec!["homework"], vec!["home"]);
+    assert_eq!(found(&edited, "home"), [0]);
+    assert!(found(&edited, "hw").is_empty());
+}
+
+#[test]
+fn tries_remove_only_the_given_strings() {
+    check_remove::<Trie1>();
+    check_remove::<Trie2>();
+    check_remove::<Trie3>();
+    check_remove::<Trie4>();
+    check_remove::<Trie5>();
+}
+
+fn tags(tl: &impl TodoLister, idx: u64) -> Vec<&str> {
+    tl.get(Index::new(idx)).unwrap().tags.iter().map(Tag::value).collect()
+}
+
+#[test]
+fn edits_keep_the_other_tags() {
+    let mut tl = TriedoList::<Trie4>::new();
+    tl.push(vec![Word::new("fix"), Word::new("bike")], Tag::from_strings(vec!["home", "Urgent"]));
+    let q = runner::parse_line("tag 0 +#weekend -#urgent +#home").unwrap();
+    assert_eq!(runner::run_query(q, &mut tl, &mut Views::new()).unwrap().to_string(), "0 \"fix bike\" #home #weekend");
+    assert_eq!(tags(&tl, 0), ["home", "weekend"]);
+    assert_eq!(tl.count(parser::search_terms("#hme #wknd").unwrap().1), 1);
+    assert_eq!(tl.count(parser::search_terms("#urg").unwrap().1), 0);
+    assert!(runner::try_run_line("tag 1 +#home", &mut tl, &mut Views::new()).is_err());
+
+    //done items keep their edits, but stay out of searches
+    tl.done_with_index(Index::new(0));
+    assert_eq!(tl.edit_tags(Index::new(0), &TagEdit{add: Tag::from_strings(vec!["later"]), remove: Vec::new()}), Some(true));
+    assert_eq!(tags(&tl, 0), ["home", "weekend", "later"]);
+    assert_eq!(tl.count(parser::search_terms("#later").unwrap().1), 0);
+    assert_eq!(tl.stats().to_string(), "0 open, 1 done\n#home 0 open, 1 done\n#later 0 open, 1 done\n#weekend 0 open, 1 done");
+}
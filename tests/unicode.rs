@@ -0,0 +1,91 @@
+use todo_swamp::*;
+use todo_swamp::sharded::ShardedTodoList;
+
+const CAFES: [&str; 3] = ["Café", "CAFÉ", "cafe\u{301}"];
+
+fn parse(line: &str) -> Result<Query, QueryError> {
+    runner::parse_line(line)
+}
+
+fn words(line: &str) -> Vec<SearchWordOrTag> {
+    match parse(line) {
+        Ok(Query::Search(sp)) => sp.params,
+        other => panic!("{} is not a search, got {:?}", line, other),
+    }
+}
+
+#[test]
+fn words_can_be_any_case_and_script() {
+    let description = ["Café", "CAFÉ", "cafe\u{301}", "v2", "日本"];
+    let tags = ["Café", "v2", "日本"];
+    assert_eq!(parse("add \"Café CAFÉ cafe\u{301} v2 日本\" #Café #v2 #日本"),
+        Ok(Query::Add(description.iter().map(|w| Word::new(w)).collect(), Tag::from_strings(tags.to_vec()))));
+    assert_eq!(words("search CAFÉ #日本 v2"), [
+        SearchWordOrTag::RawWord(String::from("CAFÉ")),
+        SearchWordOrTag::RawTag(String::from("日本")),
+        SearchWordOrTag::RawWord(String::from("v2")),
+    ]);
+    //a combining mark belongs to the word it follows
+    assert_eq!(words("search cafe\u{301}"), [SearchWordOrTag::RawWord(String::from("cafe\u{301}"))]);
+    assert!(parse("add \"日本。\"").is_err());
+}
+
+#[test]
+fn spellings_of_a_word_share_one_key() {
+    for cafe in &CAFES {
+        assert_eq!(normalise(cafe), "café");
+        assert_eq!(Word::new(cafe).key(), "café");
+        assert_eq!(Tag::new(cafe).key(), "café");
+        //the text is kept as it was written
+        assert_eq!(Word::new(cafe).value(), *cafe);
+    }
+    assert_eq!(normalise("V2"), "v2");
+    assert_eq!(normalise("日本"), "日本");
+}
+
+fn found<T: TodoLister>(tl: &T, terms: &str) -> Vec<u64> {
+    let sp = parser::search_terms(terms).unwrap().1;
+    let mut found: Vec<u64> = tl.search(sp).iter().map(|item| item.index.value()).collect();
+    found.sort_unstable();
+    found
+}
+
+//every spelling of a word finds the items written with any other, as a word or a tag
+fn check_search<T: TodoLister + Default>() {
+    let mut tl = T::default();
+    for (i, cafe) in CAFES.iter().enumerate() {
+        tl.push(vec![Word::new(cafe), Word::new("au"), Word::new("lait")], Tag::from_strings(vec![CAFES[(i + 1) % CAFES.len()]]));
+    }
+    tl.push(vec![Word::new("release"), Word::new("v2")], Tag::from_strings(vec!["v2"]));
+    tl.push(vec![Word::new("release"), Word::new("v3")], Vec::new());
+    tl.push(vec![Word::new("日本"), Word::new("trip")], Tag::from_strings(vec!["日本"]));
+    tl.push(vec![Word::new("cafe"), Word::new("bar")], Vec::new());
+
+    for cafe in &CAFES {
+        assert_eq!(found(&tl, cafe), [0, 1, 2], "{}", cafe);
+        assert_eq!(found(&tl, &format!("#{}", cafe)), [0, 1, 2], "#{}", cafe);
+    }
+    //an accented letter is a different letter from the plain one
+    assert_eq!(found(&tl, "cafe"), [6]);
+    assert_eq!(found(&tl, "cf"), [0, 1, 2, 6]);
+    assert_eq!(found(&tl, "v2"), [3]);
+    assert_eq!(found(&tl, "V"), [3, 4]);
+    assert_eq!(found(&tl, "#v2"), [3]);
+    assert_eq!(found(&tl, "2"), [3]);
+    assert_eq!(found(&tl, "日本"), [5]);
+    assert_eq!(found(&tl, "本"), [5]);
+    assert_eq!(found(&tl, "#日"), [5]);
+    assert!(found(&tl, "本日").is_empty());
+}
+
+#[test]
+fn every_backend_matches_normalised_words() {
+    check_search::<TodoList>();
+    check_search::<TodoList2>();
+    check_search::<TriedoList<Trie1>>();
+    check_search::<TriedoList<Trie2>>();
+    check_search::<TriedoList<Trie3>>();
+    check_search::<TriedoList<Trie4>>();
+    check_search::<TriedoList<Trie5>>();
+    check_search::<ShardedTodoList>();
+}
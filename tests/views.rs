@@ -0,0 +1,62 @@
+use todo_swamp::*;
+use todo_swamp::cache::CachedTodoList;
+use todo_swamp::concurrent::ConcurrentTodoList;
+use todo_swamp::session::SearchSession;
+use todo_swamp::sharded::ShardedTodoList;
+use todo_swamp::views::Views;
+
+fn answer<T: TodoLister>(tl: &mut T, views: &mut Views, line: &str) -> String {
+    match runner::try_run_line(line, tl, views) {
+        Ok(r) => r.to_string(),
+        Err(e) => e.to_string(),
+    }
+}
+
+fn check_views<T: TodoLister>(mut tl: T) {
+    let mut views = Views::new();
+    for line in &["add \"fix login bug\" #bug #mine", "add \"fix layout bug\" #bug", "add \"write docs\" #mine", "add \"fix typo\" #mine #bug"] {
+        answer(&mut tl, &mut views, line);
+    }
+    assert_eq!(answer(&mut tl, &mut views, "save Mine #mine sort:-index"), "saved @Mine");
+    assert_eq!(answer(&mut tl, &mut views, "view mine"), "3 item(s) found\n3 \"fix typo\" #mine #bug\n2 \"write docs\" #mine\n0 \"fix login bug\" #bug #mine");
+    //a search's own options come before the view's
+    assert_eq!(answer(&mut tl, &mut views, "search @mine #bug limit:1"), "1 item(s) found\n3 \"fix typo\" #mine #bug");
+    assert_eq!(answer(&mut tl, &mut views, "search fix @MINE sort:index"), "2 item(s) found\n0 \"fix login bug\" #bug #mine\n3 \"fix typo\" #mine #bug");
+    assert_eq!(answer(&mut tl, &mut views, "count @mine offset:1"), "2");
+
+    //views see the list as it is when they're run, and names in a view are resolved as it's saved
+    assert_eq!(answer(&mut tl, &mut views, "save mybugs @mine #bug"), "saved @mybugs");
+    answer(&mut tl, &mut views, "done 3");
+    answer(&mut tl, &mut views, "save mine #mine #docs");
+    assert_eq!(answer(&mut tl, &mut views, "view mybugs"), "1 item(s) found\n0 \"fix login bug\" #bug #mine");
+    assert_eq!(answer(&mut tl, &mut views, "done where @mybugs"), "1 item(s) affected");
+    assert_eq!(answer(&mut tl, &mut views, "view mybugs"), "0 item(s) found");
+
+    assert!(answer(&mut tl, &mut views, "view theirs").contains("no search is saved as @theirs"));
+    assert!(answer(&mut tl, &mut views, "search @theirs bug").contains("no search is saved as @theirs"));
+    assert!(answer(&mut tl, &mut views, "save both @mine @theirs").contains("no search is saved as @theirs"));
+    assert_eq!(views.names().collect::<Vec<_>>(), ["mine", "mybugs"]);
+}
+
+#[test]
+fn views_search_every_backend() {
+    check_views(TodoList::new());
+    check_views(TodoList2::new());
+    check_views(TriedoList::<Trie2>::new());
+    check_views(TriedoList::<Trie5>::new());
+    check_views(ShardedTodoList::with_shards(2));
+    check_views(CachedTodoList::new(TriedoList::<Trie4>::new()));
+}
+
+#[test]
+fn sessions_and_shared_lists_expand_views() {
+    let tl = ConcurrentTodoList::new(TodoList::new());
+    tl.run_line("add \"buy milk\" #shopping").unwrap();
+    tl.run_line("add \"buy bread\" #shopping").unwrap();
+    tl.run_line("save shop #shopping").unwrap();
+    let mut session = SearchSession::new();
+    let mut found = |line| tl.run_line_in_session(line, &mut session).map(|r| r.to_string());
+    assert_eq!(found("search @shop b").unwrap(), "2 item(s) found\n0 \"buy milk\" #shopping\n1 \"buy bread\" #shopping");
+    assert_eq!(found("search @shop br").unwrap(), "1 item(s) found\n1 \"buy bread\" #shopping");
+    assert!(found("search @nope").is_err());
+}